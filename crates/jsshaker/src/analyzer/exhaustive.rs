@@ -65,23 +65,34 @@ impl<'a> Analyzer<'a> {
       analyzer.include(ret_val);
       ret_val
     });
-    self.exec_exhaustively(kind, true, true, runner)
+    self.exec_exhaustively(kind, true, true, runner).0
   }
 
   pub fn exec_async_or_generator_fn(
     &mut self,
     runner: impl Fn(&mut Analyzer<'a>) -> Entity<'a> + 'a,
   ) -> Entity<'a> {
-    self.exec_exhaustively("async/generator", false, true, Rc::new(runner))
+    self.exec_exhaustively("async/generator", false, true, Rc::new(runner)).0
   }
 
+  /// For callbacks which are called later, like the ones passed to `Promise.prototype.then`.
+  /// The returned value is `Err` if it depends on states which may change before the call.
+  pub fn exec_async_callback(
+    &mut self,
+    runner: impl Fn(&mut Analyzer<'a>) -> Entity<'a> + 'a,
+  ) -> Result<Entity<'a>, Entity<'a>> {
+    let (ret, stable) = self.exec_exhaustively("async callback", false, true, Rc::new(runner));
+    if stable { Ok(ret) } else { Err(ret) }
+  }
+
+  /// Returns the value returned by the first round, and whether no outer state is read
   fn exec_exhaustively(
     &mut self,
     _kind: &str,
     drain: bool,
     register: bool,
     runner: Rc<dyn Fn(&mut Analyzer<'a>) -> Entity<'a> + 'a>,
-  ) -> Entity<'a> {
+  ) -> (Entity<'a>, bool) {
     self.push_cf_scope(
       CfScopeKind::Exhaustive(Box::new(ExhaustiveData {
         drain,
@@ -120,10 +131,12 @@ impl<'a> Analyzer<'a> {
     let CfScopeKind::Exhaustive(data) = scope.kind else {
       unreachable!();
     };
+    let mut stable = true;
     if let Some(register_deps) = data.register_deps {
+      stable = register_deps.is_empty();
       self.register_exhaustive_callbacks(drain, runner, register_deps);
     }
-    first_ret.unwrap()
+    (first_ret.unwrap(), stable)
   }
 
   fn register_exhaustive_callbacks(
@@ -24,6 +24,7 @@ use crate::{
   analyzer::rw_tracking::ReadWriteTarget,
   builtins::Builtins,
   dep::{AssocDepMap, IncludedAtoms},
+  diagnostic::{Diagnostic, DiagnosticCode},
   folding::ConstantFolder,
   mangling::Mangler,
   module::{ModuleId, Modules},
@@ -56,7 +57,7 @@ pub struct Analyzer<'a> {
   pub folder: ConstantFolder<'a>,
   pub mangler: Mangler<'a>,
   pub pending_deps: FxHashSet<ExhaustiveCallback<'a>>,
-  pub diagnostics: BTreeSet<String>,
+  pub diagnostics: BTreeSet<Diagnostic>,
   pub fn_stats: Option<RefCell<FnStats>>,
   pub symbol_registry: SymbolRegistry<'a>,
 }
@@ -97,16 +98,19 @@ impl<'a> Analyzer<'a> {
 
   pub fn throw_builtin_error(&mut self, message: impl Into<String>) {
     if self.exit_by_throw(false) == 0 {
-      self.add_diagnostic(message);
+      self.add_diagnostic(DiagnosticCode::BuiltinThrow, message);
     }
   }
 
-  pub fn add_diagnostic(&mut self, message: impl Into<String>) {
-    if !self.span_stack.is_empty() {
-      self.diagnostics.insert(format!("{} at {}", message.into(), self.format_current_span()));
-    } else {
-      self.diagnostics.insert(message.into());
+  /// The diagnostic is located at the current span, if any
+  pub fn add_diagnostic(&mut self, code: DiagnosticCode, message: impl Into<String>) {
+    let mut diagnostic = Diagnostic::new(code, message);
+    if let Some(span) = self.span_stack.last() {
+      let module_info = self.module_info();
+      diagnostic =
+        diagnostic.with_path(module_info.path.as_str()).with_span(*span, &module_info.line_index);
     }
+    self.diagnostics.insert(diagnostic);
   }
 
   pub fn current_span(&self) -> Span {
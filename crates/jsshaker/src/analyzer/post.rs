@@ -1,12 +1,25 @@
-use crate::module::ModuleId;
+use crate::{EntryExports, module::ModuleId, utils::is_rsc_boundary_module};
 
 use super::Analyzer;
 
 impl Analyzer<'_> {
-  pub fn post_analysis(&mut self) {
+  pub fn post_analysis(&mut self, entries: &[(ModuleId, EntryExports)]) {
     self.set_current_module(ModuleId::new(0));
 
-    self.include_exports(ModuleId::new(0));
+    for (module_id, exports) in entries {
+      match exports {
+        EntryExports::All => self.include_exports(*module_id),
+        EntryExports::Only(names) => self.include_exports_by_names(*module_id, names),
+        EntryExports::SideEffectsOnly => {}
+      }
+    }
+
+    for module_id in self.modules.modules.indices() {
+      let program = self.modules.modules[module_id].program;
+      if is_rsc_boundary_module(program) {
+        self.include_exports(module_id);
+      }
+    }
 
     let mut round = 0usize;
     loop {
@@ -6,7 +6,7 @@ use crate::{
   entity::Entity,
   module::ModuleId,
   scope::{CfScopeId, VariableScopeId, variable_scope::EntityOrTDZ},
-  value::{ObjectId, PropertyKeyValue, array::ArrayId},
+  value::{ObjectId, PropertyKeyValue, array::ArrayId, collection::CollectionId},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -17,6 +17,7 @@ pub enum ReadWriteTarget<'a> {
   ObjectField(ObjectId, PropertyKeyValue<'a>),
   __Object(ObjectId),
   Array(ArrayId),
+  Collection(CollectionId),
 }
 
 impl<'a> ReadWriteTarget<'a> {
@@ -50,7 +51,8 @@ impl<'a> Analyzer<'a> {
     cacheable: Option<TrackReadCacheable<'a>>,
   ) -> Option<EntityTrackerDep> {
     let target_depth = self.find_first_different_cf_scope(scope);
-    let mut registered = false;
+    // Immutable targets never trigger the registered callbacks
+    let mut registered = matches!(cacheable, Some(TrackReadCacheable::Immutable));
     let mut tracker_dep = None;
     for depth in (target_depth..self.scoping.cf.stack_len()).rev() {
       let scope = self.scoping.cf.data_at_mut(depth);
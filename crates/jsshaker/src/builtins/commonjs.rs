@@ -0,0 +1,56 @@
+use crate::{
+  Analyzer, builtin_atom, builtin_string,
+  dep::DepCollector,
+  module::CommonJsModule,
+  value::{
+    LiteralValue, ObjectProperty, ObjectPropertyValue, ObjectPrototype, PropertyKeyValue, escaped,
+  },
+};
+
+impl<'a> Analyzer<'a> {
+  pub fn create_commonjs_module(&mut self) -> CommonJsModule<'a> {
+    let exports =
+      self.new_empty_object(ObjectPrototype::Builtin(&self.builtins.prototypes.object), None);
+
+    // `module.id`, `module.filename`, etc.
+    let module = self.new_empty_object(ObjectPrototype::ImplicitOrNull, None);
+    module.init_rest(
+      self.factory,
+      ObjectPropertyValue::Property(Some(self.factory.unknown), Some(self.factory.unknown)),
+    );
+    module.keyed.borrow_mut().insert(
+      PropertyKeyValue::String(builtin_atom!("exports")),
+      ObjectProperty {
+        definite: true,
+        enumerable: true,
+        possible_values: self.factory.vec1(ObjectPropertyValue::Field((&*exports).into(), false)),
+        non_existent: DepCollector::new(self.factory.vec()),
+        key: Some(builtin_string!("exports")),
+        mangling: None,
+      },
+    );
+
+    let require = self.factory.implemented_builtin_fn("require", |analyzer, dep, this, args| {
+      let specifier = args.get(analyzer, 0).coerce_string(analyzer);
+      let Some(LiteralValue::String(specifier, _)) = specifier.get_literal(analyzer) else {
+        return escaped::builtin_call(analyzer, dep, this, args);
+      };
+
+      if let Some(known) = analyzer.builtins.get_known_module(specifier) {
+        analyzer.include((dep, args));
+        return known.namespace;
+      }
+
+      let Some(module_id) = analyzer.resolve_and_parse_module(specifier) else {
+        return escaped::builtin_call(analyzer, dep, this, args);
+      };
+
+      // Like import declarations, the call is always kept to load the module
+      analyzer.include((dep, args));
+      analyzer.exec_module(module_id);
+      analyzer.get_module_exports(module_id)
+    });
+
+    CommonJsModule { module: (&*module).into(), exports: (&*exports).into(), require }
+  }
+}
@@ -0,0 +1,83 @@
+use crate::{
+  builtins::Builtins,
+  entity::Entity,
+  init_object,
+  value::{ObjectPropertyValue, ObjectPrototype, ObjectValue, collection::CollectionKind, escaped},
+};
+
+impl<'a> Builtins<'a> {
+  pub fn init_collection_constructors(&mut self) {
+    let factory = self.factory;
+
+    let map_statics = factory.builtin_object(ObjectPrototype::Builtin(&self.prototypes.function));
+    map_statics.init_rest(factory, ObjectPropertyValue::Field(factory.unknown, true));
+    init_object!(map_statics, factory, {
+      "prototype" => factory.unknown,
+      "groupBy" => factory.pure_fn_returns_unknown,
+    });
+    self
+      .globals
+      .insert("Map", self.create_collection_constructor("Map", CollectionKind::Map, map_statics));
+
+    for (name, kind) in [
+      ("Set", CollectionKind::Set),
+      ("WeakMap", CollectionKind::WeakMap),
+      ("WeakSet", CollectionKind::WeakSet),
+    ] {
+      let statics = factory.builtin_object(ObjectPrototype::Builtin(&self.prototypes.function));
+      statics.init_rest(factory, ObjectPropertyValue::Field(factory.unknown, true));
+      init_object!(statics, factory, {
+        "prototype" => factory.unknown,
+      });
+      self.globals.insert(name, self.create_collection_constructor(name, kind, statics));
+    }
+  }
+
+  fn create_collection_constructor(
+    &self,
+    name: &'static str,
+    kind: CollectionKind,
+    statics: &'a ObjectValue<'a>,
+  ) -> Entity<'a> {
+    self.factory.implemented_builtin_constructor(
+      name,
+      move |analyzer, dep, args| {
+        let collection = analyzer.new_collection(kind);
+        let iterable = args.get(analyzer, 0);
+
+        match iterable.test_nullish() {
+          Some(true) => {}
+          Some(false) => {
+            let (elements, rest, dep) = iterable.iterated(analyzer, dep);
+            // Every element adds an entry, even if it is overwritten by a later one
+            let mut keys = analyzer.factory.vec();
+            for element in elements {
+              let (key, value) = if kind.is_set() {
+                (element, element)
+              } else {
+                let (pair, _, pair_dep) = element.destruct_as_array(analyzer, dep, 2, false);
+                (
+                  analyzer.factory.computed(pair[0], pair_dep),
+                  analyzer.factory.computed(pair[1], pair_dep),
+                )
+              };
+              keys.push(key);
+              collection.insert(analyzer, dep, key, value, false);
+            }
+            collection.deps.borrow_mut().push(analyzer.dep(keys));
+            if let Some(rest) = rest {
+              collection.untrack(analyzer.dep((dep, rest)));
+            }
+          }
+          None => {
+            let (_, _, dep, _) = escaped::iterate(analyzer, analyzer.dep((dep, iterable)));
+            collection.untrack(dep);
+          }
+        }
+
+        analyzer.factory.computed(collection.into(), dep)
+      },
+      statics,
+    )
+  }
+}
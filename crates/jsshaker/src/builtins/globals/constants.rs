@@ -86,11 +86,7 @@ impl Builtins<'_> {
       // "Float32Array" => builtin_function,
       // "Float64Array" => builtin_function,
 
-      // Keyed collections
-      "Map" => builtin_function,
-      "Set" => builtin_function,
-      "WeakMap" => builtin_function,
-      "WeakSet" => builtin_function,
+      // Keyed collections are in collection_constructors.rs
 
       // // Structured data
       // "ArrayBuffer" => builtin_function,
@@ -106,7 +102,7 @@ impl Builtins<'_> {
       // // Control abstraction objects
       // "Iterator" => builtin_function,
       // "AsyncIterator" => builtin_function,
-      // "Promise" => builtin_function,
+      // // Promise is in promise_constructor.rs
       // "GeneratorFunction" => builtin_function,
       // "AsyncGeneratorFunction" => builtin_function,
       // "Generator" => builtin_function,
@@ -1,10 +1,13 @@
+use std::f64::consts;
+
 use crate::{
   builtins::Builtins,
+  entity::Entity,
   init_namespace,
   value::{ObjectPropertyValue, ObjectPrototype},
 };
 
-impl Builtins<'_> {
+impl<'a> Builtins<'a> {
   pub fn init_math_object(&mut self) {
     let factory = self.factory;
 
@@ -13,53 +16,131 @@ impl Builtins<'_> {
 
     init_namespace!(object, factory, {
       // Value properties (constants)
-      "E" => factory.unknown_number,
-      "LN10" => factory.unknown_number,
-      "LN2" => factory.unknown_number,
-      "LOG10E" => factory.unknown_number,
-      "LOG2E" => factory.unknown_number,
-      "PI" => factory.unknown_number,
-      "SQRT1_2" => factory.unknown_number,
-      "SQRT2" => factory.unknown_number,
+      "E" => factory.number(consts::E),
+      "LN10" => factory.number(consts::LN_10),
+      "LN2" => factory.number(consts::LN_2),
+      "LOG10E" => factory.number(consts::LOG10_E),
+      "LOG2E" => factory.number(consts::LOG2_E),
+      "PI" => factory.number(consts::PI),
+      "SQRT1_2" => factory.number(consts::FRAC_1_SQRT_2),
+      "SQRT2" => factory.number(consts::SQRT_2),
 
       // Static methods
-      "abs" => factory.pure_fn_returns_number,
-      "acos" => factory.pure_fn_returns_number,
-      "acosh" => factory.pure_fn_returns_number,
-      "asin" => factory.pure_fn_returns_number,
-      "asinh" => factory.pure_fn_returns_number,
-      "atan" => factory.pure_fn_returns_number,
-      "atanh" => factory.pure_fn_returns_number,
-      "atan2" => factory.pure_fn_returns_number,
-      "cbrt" => factory.pure_fn_returns_number,
-      "ceil" => factory.pure_fn_returns_number,
-      "clz32" => factory.pure_fn_returns_number,
-      "cos" => factory.pure_fn_returns_number,
-      "cosh" => factory.pure_fn_returns_number,
-      "exp" => factory.pure_fn_returns_number,
-      "expm1" => factory.pure_fn_returns_number,
-      "floor" => factory.pure_fn_returns_number,
-      "fround" => factory.pure_fn_returns_number,
-      "hypot" => factory.pure_fn_returns_number,
-      "imul" => factory.pure_fn_returns_number,
-      "log" => factory.pure_fn_returns_number,
-      "log1p" => factory.pure_fn_returns_number,
-      "log10" => factory.pure_fn_returns_number,
-      "log2" => factory.pure_fn_returns_number,
-      "max" => factory.pure_fn_returns_number,
-      "min" => factory.pure_fn_returns_number,
-      "pow" => factory.pure_fn_returns_number,
+      "abs" => self.create_unary_math_fn("Math.abs", f64::abs),
+      "acos" => self.create_unary_math_fn("Math.acos", f64::acos),
+      "acosh" => self.create_unary_math_fn("Math.acosh", f64::acosh),
+      "asin" => self.create_unary_math_fn("Math.asin", f64::asin),
+      "asinh" => self.create_unary_math_fn("Math.asinh", f64::asinh),
+      "atan" => self.create_unary_math_fn("Math.atan", f64::atan),
+      "atanh" => self.create_unary_math_fn("Math.atanh", f64::atanh),
+      "atan2" => self.create_math_fn("Math.atan2", |args| arg(args, 0).atan2(arg(args, 1))),
+      "cbrt" => self.create_unary_math_fn("Math.cbrt", f64::cbrt),
+      "ceil" => self.create_unary_math_fn("Math.ceil", f64::ceil),
+      "clz32" => self.create_unary_math_fn("Math.clz32", |x| f64::from(to_uint32(x).leading_zeros())),
+      "cos" => self.create_unary_math_fn("Math.cos", f64::cos),
+      "cosh" => self.create_unary_math_fn("Math.cosh", f64::cosh),
+      "exp" => self.create_unary_math_fn("Math.exp", f64::exp),
+      "expm1" => self.create_unary_math_fn("Math.expm1", f64::exp_m1),
+      "floor" => self.create_unary_math_fn("Math.floor", f64::floor),
+      "fround" => self.create_unary_math_fn("Math.fround", |x| f64::from(x as f32)),
+      "hypot" => self.create_math_fn("Math.hypot", hypot),
+      "imul" => self.create_math_fn("Math.imul", |args| {
+        f64::from((to_uint32(arg(args, 0)) as i32).wrapping_mul(to_uint32(arg(args, 1)) as i32))
+      }),
+      "log" => self.create_unary_math_fn("Math.log", f64::ln),
+      "log1p" => self.create_unary_math_fn("Math.log1p", f64::ln_1p),
+      "log10" => self.create_unary_math_fn("Math.log10", f64::log10),
+      "log2" => self.create_unary_math_fn("Math.log2", f64::log2),
+      "max" => self.create_math_fn("Math.max", |args| {
+        args.iter().try_fold(f64::NEG_INFINITY, |acc, &x| {
+          // `f64::max` ignores NaN and doesn't order zeros
+          if x.is_nan() { None } else if x > acc || (x == acc && acc.is_sign_negative()) { Some(x) } else { Some(acc) }
+        }).unwrap_or(f64::NAN)
+      }),
+      "min" => self.create_math_fn("Math.min", |args| {
+        args.iter().try_fold(f64::INFINITY, |acc, &x| {
+          if x.is_nan() { None } else if x < acc || (x == acc && x.is_sign_negative()) { Some(x) } else { Some(acc) }
+        }).unwrap_or(f64::NAN)
+      }),
+      "pow" => self.create_math_fn("Math.pow", |args| pow(arg(args, 0), arg(args, 1))),
       "random" => factory.pure_fn_returns_number,
-      "round" => factory.pure_fn_returns_number,
-      "sign" => factory.pure_fn_returns_number,
-      "sin" => factory.pure_fn_returns_number,
-      "sinh" => factory.pure_fn_returns_number,
-      "sqrt" => factory.pure_fn_returns_number,
-      "tan" => factory.pure_fn_returns_number,
-      "tanh" => factory.pure_fn_returns_number,
-      "trunc" => factory.pure_fn_returns_number,
+      "round" => self.create_unary_math_fn("Math.round", round),
+      "sign" => self.create_unary_math_fn("Math.sign", |x| if x.is_nan() || x == 0.0 { x } else { x.signum() }),
+      "sin" => self.create_unary_math_fn("Math.sin", f64::sin),
+      "sinh" => self.create_unary_math_fn("Math.sinh", f64::sinh),
+      "sqrt" => self.create_unary_math_fn("Math.sqrt", f64::sqrt),
+      "tan" => self.create_unary_math_fn("Math.tan", f64::tan),
+      "tanh" => self.create_unary_math_fn("Math.tanh", f64::tanh),
+      "trunc" => self.create_unary_math_fn("Math.trunc", f64::trunc),
     });
 
     self.globals.insert("Math", object.into());
   }
+
+  /// Evaluated if all the arguments are literals, otherwise returns an unknown number
+  fn create_math_fn(&self, name: &'static str, evaluate: fn(&[f64]) -> f64) -> Entity<'a> {
+    self.factory.implemented_builtin_fn(name, move |analyzer, dep, this, args| {
+      let dep = analyzer.dep((dep, this, args));
+      if args.rest.is_none() {
+        let numbers = args
+          .elements
+          .iter()
+          .map(|arg| arg.get_literal(analyzer).and_then(|lit| lit.to_number()).map(f64::from))
+          .collect::<Option<Vec<_>>>();
+        if let Some(numbers) = numbers {
+          return analyzer.factory.computed(analyzer.factory.number(evaluate(&numbers)), dep);
+        }
+      }
+      this.unknown_mutate(analyzer, dep);
+      args.unknown_mutate(analyzer, dep);
+      analyzer.factory.computed(analyzer.factory.unknown_number, dep)
+    })
+  }
+
+  fn create_unary_math_fn(&self, name: &'static str, evaluate: fn(f64) -> f64) -> Entity<'a> {
+    self.factory.implemented_builtin_fn(name, move |analyzer, dep, this, args| {
+      let dep = analyzer.dep((dep, this, args));
+      let arg = args.get(analyzer, 0);
+      if let Some(number) = arg.get_literal(analyzer).and_then(|lit| lit.to_number()) {
+        return analyzer.factory.computed(analyzer.factory.number(evaluate(number.0)), dep);
+      }
+      this.unknown_mutate(analyzer, dep);
+      args.unknown_mutate(analyzer, dep);
+      analyzer.factory.computed(analyzer.factory.unknown_number, dep)
+    })
+  }
+}
+
+/// Missing arguments are `undefined`, which is `NaN` as a number
+fn arg(args: &[f64], index: usize) -> f64 {
+  args.get(index).copied().unwrap_or(f64::NAN)
+}
+
+/// `ToUint32`
+fn to_uint32(x: f64) -> u32 {
+  if x.is_finite() { x.trunc().rem_euclid(4294967296.0) as u32 } else { 0 }
+}
+
+fn hypot(args: &[f64]) -> f64 {
+  if args.iter().any(|x| x.is_infinite()) {
+    f64::INFINITY
+  } else {
+    args.iter().fold(0.0, |acc, x| acc.hypot(*x))
+  }
+}
+
+fn pow(base: f64, exponent: f64) -> f64 {
+  // `f64::powf` returns 1 for these cases
+  if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+    f64::NAN
+  } else {
+    base.powf(exponent)
+  }
+}
+
+/// Rounds half towards positive infinity
+fn round(x: f64) -> f64 {
+  let floor = x.floor();
+  let result = if x - floor >= 0.5 { floor + 1.0 } else { floor };
+  if result == 0.0 && x.is_sign_negative() { -0.0 } else { result }
 }
@@ -1,9 +1,11 @@
 mod array_constructor;
+mod collection_constructors;
 mod constants;
 mod date_constructor;
 mod json_object;
 mod math_object;
 mod object_constructor;
+mod promise_constructor;
 mod symbol_constructor;
 
 use super::Builtins;
@@ -17,5 +19,7 @@ impl Builtins<'_> {
     self.init_json_object();
     self.init_math_object();
     self.init_date_constructor();
+    self.init_collection_constructors();
+    self.init_promise_constructor();
   }
 }
@@ -0,0 +1,176 @@
+use oxc::{allocator, ast::ast::PropertyKind};
+
+use crate::{
+  analyzer::Analyzer,
+  builtin_string,
+  builtins::Builtins,
+  dep::Dep,
+  entity::Entity,
+  init_object,
+  value::{ObjectPropertyValue, ObjectPrototype, promise::PromiseValue},
+};
+
+type Combinator<'a> = fn(&mut Analyzer<'a>, Dep<'a>, &[&'a PromiseValue<'a>], &'a PromiseValue<'a>);
+
+impl<'a> Builtins<'a> {
+  pub fn init_promise_constructor(&mut self) {
+    let factory = self.factory;
+
+    let statics = factory.builtin_object(ObjectPrototype::Builtin(&self.prototypes.function));
+    statics.init_rest(factory, ObjectPropertyValue::Field(factory.unknown, true));
+
+    init_object!(statics, factory, {
+      "prototype" => factory.unknown,
+      "all" => self.create_promise_combinator("Promise.all", combine_all),
+      "allSettled" => self.create_promise_combinator("Promise.allSettled", combine_all_settled),
+      "any" => self.create_promise_combinator("Promise.any", combine_any),
+      "race" => self.create_promise_combinator("Promise.race", combine_race),
+      "reject" => self.create_promise_reject_impl(),
+      "resolve" => self.create_promise_resolve_impl(),
+      "try" => factory.unknown,
+      "withResolvers" => factory.pure_fn_returns_unknown,
+    });
+
+    self.globals.insert(
+      "Promise",
+      factory.implemented_builtin_constructor(
+        "Promise",
+        |analyzer, dep, args| {
+          let executor = args.get(analyzer, 0);
+          analyzer.construct_promise(dep, executor)
+        },
+        statics,
+      ),
+    );
+  }
+
+  fn create_promise_resolve_impl(&self) -> Entity<'a> {
+    self.factory.implemented_builtin_fn("Promise.resolve", |analyzer, dep, _, args| {
+      let value = args.get(analyzer, 0);
+      analyzer.promise_resolve(dep, value)
+    })
+  }
+
+  fn create_promise_reject_impl(&self) -> Entity<'a> {
+    self.factory.implemented_builtin_fn("Promise.reject", |analyzer, dep, _, args| {
+      let reason = args.get(analyzer, 0);
+      let promise = analyzer.new_promise();
+      promise.add_rejected(analyzer, analyzer.factory.computed(reason, dep));
+      promise.deps.borrow_mut().push(dep);
+      analyzer.factory.computed(promise.into(), dep)
+    })
+  }
+
+  fn create_promise_combinator(&self, name: &'static str, combine: Combinator<'a>) -> Entity<'a> {
+    self.factory.implemented_builtin_fn(name, move |analyzer, dep, _, args| {
+      let iterable = args.get(analyzer, 0);
+      let (elements, rest, dep) = iterable.iterated(analyzer, dep);
+      let promises = elements
+        .into_iter()
+        .map(|element| analyzer.resolved_promise(dep, element))
+        .collect::<Vec<_>>();
+
+      let result = analyzer.new_promise();
+      if let Some(rest) = rest {
+        result.add_unknown(analyzer, analyzer.dep((dep, rest)));
+      } else {
+        combine(analyzer, dep, &promises, result);
+      }
+      result.deps.borrow_mut().push(dep);
+      analyzer.factory.computed(result.into(), dep)
+    })
+  }
+}
+
+/// Fulfilled with all the values, or rejected with any of the reasons
+fn combine_all<'a>(
+  analyzer: &mut Analyzer<'a>,
+  dep: Dep<'a>,
+  promises: &[&'a PromiseValue<'a>],
+  result: &'a PromiseValue<'a>,
+) {
+  let values = analyzer.new_empty_array();
+  let mut all_fulfillable = true;
+  for promise in promises {
+    if let Some(value) = promise.fulfilled.get() {
+      values.push_element(value);
+    } else {
+      all_fulfillable = false;
+    }
+    if let Some(reason) = promise.rejected.get() {
+      result.add_rejected(analyzer, reason);
+    }
+  }
+  if all_fulfillable {
+    result.add_fulfilled(analyzer, analyzer.factory.computed(values.into(), dep));
+  }
+}
+
+/// Fulfilled with the outcome objects once all the promises are settled
+fn combine_all_settled<'a>(
+  analyzer: &mut Analyzer<'a>,
+  dep: Dep<'a>,
+  promises: &[&'a PromiseValue<'a>],
+  result: &'a PromiseValue<'a>,
+) {
+  let outcomes = analyzer.new_empty_array();
+  for promise in promises {
+    let mut possible_outcomes = analyzer.factory.vec();
+    for (status, key, value) in [
+      (builtin_string!("fulfilled"), builtin_string!("value"), promise.fulfilled.get()),
+      (builtin_string!("rejected"), builtin_string!("reason"), promise.rejected.get()),
+    ] {
+      if let Some(value) = value {
+        let object = analyzer
+          .new_empty_object(ObjectPrototype::Builtin(&analyzer.builtins.prototypes.object), None);
+        object.init_property(analyzer, PropertyKind::Init, builtin_string!("status"), status, true);
+        object.init_property(analyzer, PropertyKind::Init, key, value, true);
+        possible_outcomes.push(object.into());
+      }
+    }
+    let Some(outcome) = analyzer.factory.try_union(possible_outcomes) else {
+      // Never settled
+      return;
+    };
+    outcomes.push_element(outcome);
+  }
+  result.add_fulfilled(analyzer, analyzer.factory.computed(outcomes.into(), dep));
+}
+
+/// Fulfilled with any of the values, or rejected with an `AggregateError`
+fn combine_any<'a>(
+  analyzer: &mut Analyzer<'a>,
+  dep: Dep<'a>,
+  promises: &[&'a PromiseValue<'a>],
+  result: &'a PromiseValue<'a>,
+) {
+  for promise in promises {
+    if let Some(value) = promise.fulfilled.get() {
+      result.add_fulfilled(analyzer, value);
+    }
+  }
+  if promises.iter().all(|promise| promise.rejected.get().is_some()) {
+    let reasons = allocator::Vec::from_iter_in(
+      promises.iter().filter_map(|promise| promise.rejected.get()),
+      analyzer.allocator,
+    );
+    result.add_rejected(analyzer, analyzer.factory.computed_unknown((dep, reasons)));
+  }
+}
+
+/// Settled as the first settled promise
+fn combine_race<'a>(
+  analyzer: &mut Analyzer<'a>,
+  _dep: Dep<'a>,
+  promises: &[&'a PromiseValue<'a>],
+  result: &'a PromiseValue<'a>,
+) {
+  for promise in promises {
+    if let Some(value) = promise.fulfilled.get() {
+      result.add_fulfilled(analyzer, value);
+    }
+    if let Some(reason) = promise.rejected.get() {
+      result.add_rejected(analyzer, reason);
+    }
+  }
+}
@@ -1,3 +1,4 @@
+mod commonjs;
 mod globals;
 mod import_meta;
 mod known_modules;
@@ -0,0 +1,146 @@
+use super::{BuiltinPrototype, object::create_object_prototype};
+use crate::{
+  analyzer::{Analyzer, Factory},
+  dep::Dep,
+  entity::Entity,
+  init_prototype,
+  value::{
+    ArgumentsValue,
+    collection::{CollectionKind, CollectionValue},
+    escaped,
+  },
+};
+
+/// Falls back to an unknown call if `this` is not a tracked collection of the given kind
+fn collection_method<'a>(
+  factory: &'a Factory<'a>,
+  name: &'static str,
+  kind: CollectionKind,
+  method: impl Fn(
+    &'a CollectionValue<'a>,
+    &mut Analyzer<'a>,
+    Dep<'a>,
+    Entity<'a>,
+    ArgumentsValue<'a>,
+  ) -> Entity<'a>
+  + 'a,
+) -> Entity<'a> {
+  factory.implemented_builtin_fn(name, move |analyzer, dep, this, args| {
+    match this.as_collection() {
+      Some(collection) if collection.kind == kind && !collection.included.get() => {
+        let dep = analyzer.dep((dep, this.get_shallow_dep(analyzer.factory)));
+        method(collection, analyzer, dep, this, args)
+      }
+      _ => escaped::builtin_call(analyzer, dep, this, args),
+    }
+  })
+}
+
+pub fn create_map_prototype<'a>(factory: &'a Factory<'a>) -> BuiltinPrototype<'a> {
+  let kind = CollectionKind::Map;
+  init_prototype!("Map", create_object_prototype(factory), {
+    "clear": collection_method(factory, "Map::clear", kind, |collection, analyzer, dep, this, args| {
+      collection.clear_entries(analyzer, dep, this, args)
+    }),
+    "delete": collection_method(factory, "Map::delete", kind, |collection, analyzer, dep, this, args| {
+      collection.delete_entry(analyzer, dep, this, args)
+    }),
+    "entries": collection_method(factory, "Map::entries", kind, |collection, analyzer, dep, _, _| {
+      collection.iterator(analyzer, dep)
+    }),
+    "forEach": collection_method(factory, "Map::forEach", kind, |collection, analyzer, dep, this, args| {
+      collection.for_each(analyzer, dep, this, args)
+    }),
+    "get": collection_method(factory, "Map::get", kind, |collection, analyzer, dep, _, args| {
+      let key = args.get(analyzer, 0);
+      collection.get_entry(analyzer, dep, key)
+    }),
+    "has": collection_method(factory, "Map::has", kind, |collection, analyzer, dep, _, args| {
+      let key = args.get(analyzer, 0);
+      collection.has_entry(analyzer, dep, key)
+    }),
+    "keys": collection_method(factory, "Map::keys", kind, |collection, analyzer, dep, _, _| {
+      collection.iterator(analyzer, dep)
+    }),
+    "set": collection_method(factory, "Map::set", kind, |collection, analyzer, dep, this, args| {
+      collection.set_entry(analyzer, dep, this, args)
+    }),
+    "values": collection_method(factory, "Map::values", kind, |collection, analyzer, dep, _, _| {
+      collection.iterator(analyzer, dep)
+    }),
+  })
+}
+
+pub fn create_set_prototype<'a>(factory: &'a Factory<'a>) -> BuiltinPrototype<'a> {
+  let kind = CollectionKind::Set;
+  init_prototype!("Set", create_object_prototype(factory), {
+    "add": collection_method(factory, "Set::add", kind, |collection, analyzer, dep, this, args| {
+      collection.set_entry(analyzer, dep, this, args)
+    }),
+    "clear": collection_method(factory, "Set::clear", kind, |collection, analyzer, dep, this, args| {
+      collection.clear_entries(analyzer, dep, this, args)
+    }),
+    "delete": collection_method(factory, "Set::delete", kind, |collection, analyzer, dep, this, args| {
+      collection.delete_entry(analyzer, dep, this, args)
+    }),
+    "difference": factory.pure_fn_returns_unknown,
+    "entries": collection_method(factory, "Set::entries", kind, |collection, analyzer, dep, _, _| {
+      collection.iterator(analyzer, dep)
+    }),
+    "forEach": collection_method(factory, "Set::forEach", kind, |collection, analyzer, dep, this, args| {
+      collection.for_each(analyzer, dep, this, args)
+    }),
+    "has": collection_method(factory, "Set::has", kind, |collection, analyzer, dep, _, args| {
+      let key = args.get(analyzer, 0);
+      collection.has_entry(analyzer, dep, key)
+    }),
+    "intersection": factory.pure_fn_returns_unknown,
+    "isDisjointFrom": factory.pure_fn_returns_boolean,
+    "isSubsetOf": factory.pure_fn_returns_boolean,
+    "isSupersetOf": factory.pure_fn_returns_boolean,
+    "keys": collection_method(factory, "Set::keys", kind, |collection, analyzer, dep, _, _| {
+      collection.iterator(analyzer, dep)
+    }),
+    "symmetricDifference": factory.pure_fn_returns_unknown,
+    "union": factory.pure_fn_returns_unknown,
+    "values": collection_method(factory, "Set::values", kind, |collection, analyzer, dep, _, _| {
+      collection.iterator(analyzer, dep)
+    }),
+  })
+}
+
+pub fn create_weak_map_prototype<'a>(factory: &'a Factory<'a>) -> BuiltinPrototype<'a> {
+  let kind = CollectionKind::WeakMap;
+  init_prototype!("WeakMap", create_object_prototype(factory), {
+    "delete": collection_method(factory, "WeakMap::delete", kind, |collection, analyzer, dep, this, args| {
+      collection.delete_entry(analyzer, dep, this, args)
+    }),
+    "get": collection_method(factory, "WeakMap::get", kind, |collection, analyzer, dep, _, args| {
+      let key = args.get(analyzer, 0);
+      collection.get_entry(analyzer, dep, key)
+    }),
+    "has": collection_method(factory, "WeakMap::has", kind, |collection, analyzer, dep, _, args| {
+      let key = args.get(analyzer, 0);
+      collection.has_entry(analyzer, dep, key)
+    }),
+    "set": collection_method(factory, "WeakMap::set", kind, |collection, analyzer, dep, this, args| {
+      collection.set_entry(analyzer, dep, this, args)
+    }),
+  })
+}
+
+pub fn create_weak_set_prototype<'a>(factory: &'a Factory<'a>) -> BuiltinPrototype<'a> {
+  let kind = CollectionKind::WeakSet;
+  init_prototype!("WeakSet", create_object_prototype(factory), {
+    "add": collection_method(factory, "WeakSet::add", kind, |collection, analyzer, dep, this, args| {
+      collection.set_entry(analyzer, dep, this, args)
+    }),
+    "delete": collection_method(factory, "WeakSet::delete", kind, |collection, analyzer, dep, this, args| {
+      collection.delete_entry(analyzer, dep, this, args)
+    }),
+    "has": collection_method(factory, "WeakSet::has", kind, |collection, analyzer, dep, _, args| {
+      let key = args.get(analyzer, 0);
+      collection.has_entry(analyzer, dep, key)
+    }),
+  })
+}
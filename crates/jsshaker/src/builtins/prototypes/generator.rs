@@ -0,0 +1,41 @@
+use super::{BuiltinPrototype, object::create_object_prototype};
+use crate::{
+  analyzer::{Analyzer, Factory},
+  dep::Dep,
+  entity::Entity,
+  init_prototype,
+  value::{ArgumentsValue, ValueTrait, escaped, generator::GeneratorValue},
+};
+
+/// Falls back to an unknown call if `this` is not a tracked generator
+fn generator_method<'a>(
+  factory: &'a Factory<'a>,
+  name: &'static str,
+  method: impl Fn(&'a GeneratorValue<'a>, &mut Analyzer<'a>, Dep<'a>, ArgumentsValue<'a>) -> Entity<'a>
+  + 'a,
+) -> Entity<'a> {
+  factory.implemented_builtin_fn(name, move |analyzer, dep, this, args| match this.as_generator() {
+    Some(generator) if !generator.included.get() => {
+      let dep = analyzer.dep((dep, this.get_shallow_dep(analyzer.factory)));
+      method(generator, analyzer, dep, args)
+    }
+    _ => escaped::builtin_call(analyzer, dep, this, args),
+  })
+}
+
+pub fn create_generator_prototype<'a>(factory: &'a Factory<'a>) -> BuiltinPrototype<'a> {
+  init_prototype!("Generator", create_object_prototype(factory), {
+    "next": generator_method(factory, "Generator::next", |generator, analyzer, dep, args| {
+      generator.next(analyzer, dep, args)
+    }),
+    "return": generator_method(factory, "Generator::return", |generator, analyzer, dep, args| {
+      let value = args.get(analyzer, 0);
+      generator.r#return(analyzer, dep, value)
+    }),
+    "throw": generator_method(factory, "Generator::throw", |generator, analyzer, dep, args| {
+      // The thrown value may be caught by the body, which is not tracked
+      generator.include(analyzer);
+      escaped::builtin_call(analyzer, dep, generator.into(), args)
+    }),
+  })
+}
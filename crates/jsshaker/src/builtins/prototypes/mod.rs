@@ -1,7 +1,9 @@
 mod array;
 mod bigint;
 mod boolean;
+mod collection;
 mod function;
+mod generator;
 mod null;
 mod number;
 mod object;
@@ -112,13 +114,18 @@ pub struct BuiltinPrototypes<'a> {
   pub bigint: BuiltinPrototype<'a>,
   pub boolean: BuiltinPrototype<'a>,
   pub function: BuiltinPrototype<'a>,
+  pub generator: BuiltinPrototype<'a>,
+  pub map: BuiltinPrototype<'a>,
   pub null: BuiltinPrototype<'a>,
   pub number: BuiltinPrototype<'a>,
   pub object: BuiltinPrototype<'a>,
   pub promise: BuiltinPrototype<'a>,
   pub regexp: BuiltinPrototype<'a>,
+  pub set: BuiltinPrototype<'a>,
   pub string: BuiltinPrototype<'a>,
   pub symbol: BuiltinPrototype<'a>,
+  pub weak_map: BuiltinPrototype<'a>,
+  pub weak_set: BuiltinPrototype<'a>,
 }
 
 impl<'a> Builtins<'a> {
@@ -128,13 +135,18 @@ impl<'a> Builtins<'a> {
       bigint: bigint::create_bigint_prototype(factory),
       boolean: boolean::create_boolean_prototype(factory),
       function: function::create_function_prototype(factory),
+      generator: generator::create_generator_prototype(factory),
+      map: collection::create_map_prototype(factory),
       null: null::create_null_prototype(factory),
       number: number::create_number_prototype(factory),
       object: object::create_object_prototype(factory),
       promise: promise::create_promise_prototype(factory),
       regexp: regexp::create_regexp_prototype(factory),
+      set: collection::create_set_prototype(factory),
       string: string::create_string_prototype(factory),
       symbol: symbol::create_symbol_prototype(factory),
+      weak_map: collection::create_weak_map_prototype(factory),
+      weak_set: collection::create_weak_set_prototype(factory),
     })
   }
 }
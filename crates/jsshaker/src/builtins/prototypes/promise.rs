@@ -1,10 +1,42 @@
 use super::{BuiltinPrototype, object::create_object_prototype};
-use crate::{analyzer::Factory, init_prototype};
+use crate::{
+  analyzer::{Analyzer, Factory},
+  dep::Dep,
+  entity::Entity,
+  init_prototype,
+  value::{ArgumentsValue, escaped, promise::PromiseValue},
+};
+
+/// Falls back to an unknown call if `this` is not a tracked promise
+fn promise_method<'a>(
+  factory: &'a Factory<'a>,
+  name: &'static str,
+  method: impl Fn(&'a PromiseValue<'a>, &mut Analyzer<'a>, Dep<'a>, ArgumentsValue<'a>) -> Entity<'a>
+  + 'a,
+) -> Entity<'a> {
+  factory.implemented_builtin_fn(name, move |analyzer, dep, this, args| match this.as_promise() {
+    Some(promise) if !promise.included.get() => {
+      let dep = analyzer.dep((dep, this.get_shallow_dep(analyzer.factory)));
+      method(promise, analyzer, dep, args)
+    }
+    _ => escaped::builtin_call(analyzer, dep, this, args),
+  })
+}
 
 pub fn create_promise_prototype<'a>(factory: &'a Factory<'a>) -> BuiltinPrototype<'a> {
   init_prototype!("Promise", create_object_prototype(factory), {
-    "finally": factory.unknown,
-    "then": factory.unknown,
-    "catch": factory.unknown,
+    "finally": promise_method(factory, "Promise::finally", |promise, analyzer, dep, args| {
+      let on_finally = args.get(analyzer, 0);
+      promise.finally(analyzer, dep, on_finally)
+    }),
+    "then": promise_method(factory, "Promise::then", |promise, analyzer, dep, args| {
+      let on_fulfilled = args.get(analyzer, 0);
+      let on_rejected = args.get(analyzer, 1);
+      promise.then(analyzer, dep, on_fulfilled, on_rejected)
+    }),
+    "catch": promise_method(factory, "Promise::catch", |promise, analyzer, dep, args| {
+      let on_rejected = args.get(analyzer, 0);
+      promise.then(analyzer, dep, analyzer.factory.undefined, on_rejected)
+    }),
   })
 }
@@ -1,55 +1,480 @@
 use super::{BuiltinPrototype, object::create_object_prototype};
-use crate::{analyzer::Factory, init_prototype};
+use crate::{
+  analyzer::{Analyzer, Factory},
+  entity::Entity,
+  init_prototype,
+  value::{ArgumentsValue, LiteralValue},
+};
+
+/// Results longer than this are not evaluated
+const MAX_EVALUATED_LENGTH: usize = 1 << 16;
+
+type Evaluate<'a> = fn(&mut Analyzer<'a>, &'a str, &[LiteralValue<'a>]) -> Option<Entity<'a>>;
+
+/// Evaluates the method if `this` is a string literal and the arguments are literals
+fn evaluate_string_method<'a>(
+  analyzer: &mut Analyzer<'a>,
+  this: Entity<'a>,
+  args: ArgumentsValue<'a>,
+  evaluate: Evaluate<'a>,
+) -> Option<Entity<'a>> {
+  let this = this.get_literal(analyzer)?;
+  let LiteralValue::String(value, _) = this else {
+    return None;
+  };
+  if args.rest.is_some() {
+    return None;
+  }
+  let args =
+    args.elements.iter().map(|arg| arg.get_literal(analyzer)).collect::<Option<Vec<_>>>()?;
+  if may_have_lone_surrogates(&this) || args.iter().any(may_have_lone_surrogates) {
+    return None;
+  }
+  evaluate(analyzer, value.as_str(), &args)
+}
+
+/// oxc stores a lone surrogate as U+FFFD followed by its hex code, which is not the real string
+fn may_have_lone_surrogates(literal: &LiteralValue) -> bool {
+  matches!(literal, LiteralValue::String(value, _) if value.contains('\u{FFFD}'))
+}
+
+/// Falls back to `fallback` like a pure function if the method can't be evaluated
+fn string_method<'a>(
+  factory: &'a Factory<'a>,
+  name: &'static str,
+  fallback: Entity<'a>,
+  evaluate: Evaluate<'a>,
+) -> Entity<'a> {
+  factory.implemented_builtin_fn(name, move |analyzer, dep, this, args| {
+    let dep = analyzer.dep((dep, this, args));
+    if let Some(result) = evaluate_string_method(analyzer, this, args, evaluate) {
+      return analyzer.factory.computed(result, dep);
+    }
+    this.unknown_mutate(analyzer, dep);
+    args.unknown_mutate(analyzer, dep);
+    analyzer.factory.computed(fallback, dep)
+  })
+}
+
+/// `String.prototype.split`, which returns an array of unknown strings if it can't be evaluated
+fn string_split<'a>(factory: &'a Factory<'a>) -> Entity<'a> {
+  factory.implemented_builtin_fn("String::split", |analyzer, dep, this, args| {
+    let dep = analyzer.dep((dep, this, args));
+    if let Some(result) = evaluate_string_method(analyzer, this, args, split) {
+      return analyzer.factory.computed(result, dep);
+    }
+    this.unknown_mutate(analyzer, dep);
+    args.unknown_mutate(analyzer, dep);
+    let array = analyzer.new_empty_array();
+    array.init_rest(analyzer.factory.unknown_string);
+    analyzer.factory.computed(array.into(), dep)
+  })
+}
+
+fn number_arg(args: &[LiteralValue], index: usize) -> Option<Option<f64>> {
+  match args.get(index) {
+    None | Some(LiteralValue::Undefined) => Some(None),
+    Some(arg) => arg.to_number().map(|n| Some(n.0)),
+  }
+}
+
+fn string_arg<'a>(
+  analyzer: &Analyzer<'a>,
+  args: &[LiteralValue<'a>],
+  index: usize,
+) -> Option<&'a str> {
+  match args.get(index).copied().unwrap_or(LiteralValue::Undefined) {
+    LiteralValue::Symbol(_) => None,
+    arg => Some(arg.to_string(analyzer.allocator).as_str()),
+  }
+}
+
+/// `ToIntegerOrInfinity`
+fn to_integer(n: f64) -> f64 {
+  if n.is_nan() { 0.0 } else { n.trunc() }
+}
+
+/// Clamps the integer index to `0..=len`, negative ones count from the end if `relative`
+fn resolve_index(n: Option<f64>, len: usize, default: usize, relative: bool) -> usize {
+  let Some(n) = n else {
+    return default;
+  };
+  let n = to_integer(n);
+  let n = if relative && n < 0.0 { len as f64 + n } else { n };
+  n.clamp(0.0, len as f64) as usize
+}
+
+fn find_units(haystack: &[u16], needle: &[u16], from: usize) -> Option<usize> {
+  let last = haystack.len().checked_sub(needle.len())?;
+  (from..=last).find(|&i| haystack[i..i + needle.len()] == *needle)
+}
+
+fn string_from_units<'a>(analyzer: &Analyzer<'a>, units: &[u16]) -> Option<Entity<'a>> {
+  // Lone surrogates can't be represented
+  let value = String::from_utf16(units).ok()?;
+  Some(analyzer.factory.unmangable_string(value))
+}
+
+fn is_js_whitespace(c: char) -> bool {
+  matches!(
+    c,
+    '\u{9}' | '\u{A}' | '\u{B}' | '\u{C}' | '\u{D}' | ' ' | '\u{A0}' | '\u{1680}' | '\u{2000}'
+      ..='\u{200A}' | '\u{2028}' | '\u{2029}' | '\u{202F}' | '\u{205F}' | '\u{3000}' | '\u{FEFF}'
+  )
+}
+
+fn at<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+) -> Option<Entity<'a>> {
+  let units = value.encode_utf16().collect::<Vec<_>>();
+  let pos = to_integer(number_arg(args, 0)?.unwrap_or(0.0));
+  let pos = if pos < 0.0 { units.len() as f64 + pos } else { pos };
+  if pos < 0.0 || pos >= units.len() as f64 {
+    return Some(analyzer.factory.undefined);
+  }
+  string_from_units(analyzer, &units[pos as usize..pos as usize + 1])
+}
+
+fn char_at<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+) -> Option<Entity<'a>> {
+  let units = value.encode_utf16().collect::<Vec<_>>();
+  let pos = to_integer(number_arg(args, 0)?.unwrap_or(0.0));
+  if pos < 0.0 || pos >= units.len() as f64 {
+    return Some(analyzer.factory.unmangable_string(""));
+  }
+  string_from_units(analyzer, &units[pos as usize..pos as usize + 1])
+}
+
+fn char_code_at<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+) -> Option<Entity<'a>> {
+  let pos = to_integer(number_arg(args, 0)?.unwrap_or(0.0));
+  if pos < 0.0 {
+    return Some(analyzer.factory.nan);
+  }
+  Some(match value.encode_utf16().nth(pos as usize) {
+    Some(unit) => analyzer.factory.number(f64::from(unit)),
+    None => analyzer.factory.nan,
+  })
+}
+
+fn code_point_at<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+) -> Option<Entity<'a>> {
+  let units = value.encode_utf16().collect::<Vec<_>>();
+  let pos = to_integer(number_arg(args, 0)?.unwrap_or(0.0));
+  if pos < 0.0 || pos >= units.len() as f64 {
+    return Some(analyzer.factory.undefined);
+  }
+  let pos = pos as usize;
+  let code_point = char::decode_utf16(units[pos..].iter().copied())
+    .next()
+    .unwrap()
+    .map_or_else(|err| u32::from(err.unpaired_surrogate()), u32::from);
+  Some(analyzer.factory.number(f64::from(code_point)))
+}
+
+fn concat<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+) -> Option<Entity<'a>> {
+  let mut result = value.to_string();
+  for index in 0..args.len() {
+    result.push_str(string_arg(analyzer, args, index)?);
+  }
+  (result.len() <= MAX_EVALUATED_LENGTH).then(|| analyzer.factory.unmangable_string(result))
+}
+
+fn ends_with<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+) -> Option<Entity<'a>> {
+  let units = value.encode_utf16().collect::<Vec<_>>();
+  let search = string_arg(analyzer, args, 0)?.encode_utf16().collect::<Vec<_>>();
+  let end = resolve_index(number_arg(args, 1)?, units.len(), units.len(), false);
+  let result = end.checked_sub(search.len()).is_some_and(|start| units[start..end] == *search);
+  Some(analyzer.factory.boolean(result))
+}
+
+fn includes<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+) -> Option<Entity<'a>> {
+  let units = value.encode_utf16().collect::<Vec<_>>();
+  let search = string_arg(analyzer, args, 0)?.encode_utf16().collect::<Vec<_>>();
+  let start = resolve_index(number_arg(args, 1)?, units.len(), 0, false);
+  Some(analyzer.factory.boolean(find_units(&units, &search, start).is_some()))
+}
+
+fn index_of<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+) -> Option<Entity<'a>> {
+  let units = value.encode_utf16().collect::<Vec<_>>();
+  let search = string_arg(analyzer, args, 0)?.encode_utf16().collect::<Vec<_>>();
+  let start = resolve_index(number_arg(args, 1)?, units.len(), 0, false);
+  let index = find_units(&units, &search, start).map_or(-1.0, |index| index as f64);
+  Some(analyzer.factory.number(index))
+}
+
+fn last_index_of<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+) -> Option<Entity<'a>> {
+  let units = value.encode_utf16().collect::<Vec<_>>();
+  let search = string_arg(analyzer, args, 0)?.encode_utf16().collect::<Vec<_>>();
+  let pos = match number_arg(args, 1)? {
+    Some(n) if !n.is_nan() => Some(n),
+    _ => None,
+  };
+  let start = resolve_index(pos, units.len(), units.len(), false);
+  let index = units
+    .len()
+    .checked_sub(search.len())
+    .and_then(|last| (0..=start.min(last)).rev().find(|&i| units[i..i + search.len()] == *search));
+  Some(analyzer.factory.number(index.map_or(-1.0, |index| index as f64)))
+}
+
+fn pad<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+  at_start: bool,
+) -> Option<Entity<'a>> {
+  let units = value.encode_utf16().collect::<Vec<_>>();
+  let max_length = to_integer(number_arg(args, 0)?.unwrap_or(0.0));
+  if max_length <= units.len() as f64 {
+    return Some(analyzer.factory.unmangable_string(value));
+  }
+  if max_length > MAX_EVALUATED_LENGTH as f64 {
+    return None;
+  }
+  let fill = match args.get(1) {
+    None | Some(LiteralValue::Undefined) => " ",
+    Some(_) => string_arg(analyzer, args, 1)?,
+  };
+  if fill.is_empty() {
+    return Some(analyzer.factory.unmangable_string(value));
+  }
+  let fill_len = max_length as usize - units.len();
+  let filler = fill.encode_utf16().cycle().take(fill_len);
+  let result = if at_start {
+    filler.chain(units.iter().copied()).collect::<Vec<_>>()
+  } else {
+    units.iter().copied().chain(filler).collect::<Vec<_>>()
+  };
+  string_from_units(analyzer, &result)
+}
+
+fn repeat<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+) -> Option<Entity<'a>> {
+  let count = to_integer(number_arg(args, 0)?.unwrap_or(0.0));
+  // Negative or infinite counts throw
+  if count < 0.0 || count.is_infinite() {
+    return None;
+  }
+  if value.len() as f64 * count > MAX_EVALUATED_LENGTH as f64 {
+    return None;
+  }
+  Some(analyzer.factory.unmangable_string(value.repeat(count as usize)))
+}
+
+fn replace<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+  all: bool,
+) -> Option<Entity<'a>> {
+  let search = string_arg(analyzer, args, 0)?;
+  let replacement = string_arg(analyzer, args, 1)?;
+  // Replacement patterns are not supported
+  if replacement.contains('$') || (all && search.is_empty()) {
+    return None;
+  }
+  let result =
+    if all { value.replace(search, replacement) } else { value.replacen(search, replacement, 1) };
+  (result.len() <= MAX_EVALUATED_LENGTH).then(|| analyzer.factory.unmangable_string(result))
+}
+
+fn slice<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+) -> Option<Entity<'a>> {
+  let units = value.encode_utf16().collect::<Vec<_>>();
+  let start = resolve_index(number_arg(args, 0)?, units.len(), 0, true);
+  let end = resolve_index(number_arg(args, 1)?, units.len(), units.len(), true);
+  string_from_units(analyzer, &units[start..end.max(start)])
+}
+
+fn substring<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+) -> Option<Entity<'a>> {
+  let units = value.encode_utf16().collect::<Vec<_>>();
+  let start = resolve_index(number_arg(args, 0)?, units.len(), 0, false);
+  let end = resolve_index(number_arg(args, 1)?, units.len(), units.len(), false);
+  string_from_units(analyzer, &units[start.min(end)..start.max(end)])
+}
+
+fn substr<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+) -> Option<Entity<'a>> {
+  let units = value.encode_utf16().collect::<Vec<_>>();
+  let start = resolve_index(number_arg(args, 0)?, units.len(), 0, true);
+  let length = resolve_index(number_arg(args, 1)?, units.len() - start, units.len() - start, false);
+  string_from_units(analyzer, &units[start..start + length])
+}
+
+fn split<'a>(
+  analyzer: &mut Analyzer<'a>,
+  value: &'a str,
+  args: &[LiteralValue<'a>],
+) -> Option<Entity<'a>> {
+  let limit = match number_arg(args, 1)? {
+    // `ToUint32`
+    Some(n) if n.is_finite() => to_integer(n).rem_euclid(4294967296.0) as usize,
+    Some(_) => 0,
+    None => usize::MAX,
+  };
+  let parts = match args.first() {
+    None | Some(LiteralValue::Undefined) => vec![value.to_string()],
+    Some(_) => {
+      let separator = string_arg(analyzer, args, 0)?;
+      if separator.is_empty() {
+        let units = value.encode_utf16().collect::<Vec<_>>();
+        let mut parts = vec![];
+        for unit in units.iter().take(limit) {
+          parts.push(String::from_utf16(&[*unit]).ok()?);
+        }
+        parts
+      } else {
+        value.split(separator).map(str::to_string).collect()
+      }
+    }
+  };
+
+  let array = analyzer.new_empty_array();
+  for part in parts.into_iter().take(limit) {
+    array.push_element(analyzer.factory.unmangable_string(part));
+  }
+  Some(array.into())
+}
+
+fn trim(value: &str, start: bool, end: bool) -> &str {
+  let value = if start { value.trim_start_matches(is_js_whitespace) } else { value };
+  if end { value.trim_end_matches(is_js_whitespace) } else { value }
+}
 
 pub fn create_string_prototype<'a>(factory: &'a Factory<'a>) -> BuiltinPrototype<'a> {
+  let string_or_undefined = factory.union((factory.unknown_string, factory.undefined));
+  let number_or_undefined = factory.union((factory.unknown_number, factory.undefined));
   init_prototype!("String", create_object_prototype(factory), {
     "anchor": factory.pure_fn_returns_string,
-    "at": factory.pure_fn_returns_unknown,
+    "at": string_method(factory, "String::at", string_or_undefined, at),
     "big": factory.pure_fn_returns_string,
     "blink": factory.pure_fn_returns_string,
     "bold": factory.pure_fn_returns_string,
-    "charAt": factory.pure_fn_returns_string,
-    "charCodeAt": factory.pure_fn_returns_unknown,
-    "codePointAt": factory.pure_fn_returns_unknown,
-    "concat": factory.pure_fn_returns_string,
-    "endsWith": factory.pure_fn_returns_unknown,
+    "charAt": string_method(factory, "String::charAt", factory.unknown_string, char_at),
+    "charCodeAt": string_method(factory, "String::charCodeAt", factory.unknown_number, char_code_at),
+    "codePointAt": string_method(factory, "String::codePointAt", number_or_undefined, code_point_at),
+    "concat": string_method(factory, "String::concat", factory.unknown_string, concat),
+    "endsWith": string_method(factory, "String::endsWith", factory.unknown_boolean, ends_with),
     "fixed": factory.pure_fn_returns_string,
     "fontcolor": factory.pure_fn_returns_string,
     "fontsize": factory.pure_fn_returns_string,
-    "includes": factory.pure_fn_returns_unknown,
-    "indexOf": factory.pure_fn_returns_unknown,
+    "includes": string_method(factory, "String::includes", factory.unknown_boolean, includes),
+    "indexOf": string_method(factory, "String::indexOf", factory.unknown_number, index_of),
+    "isWellFormed": string_method(factory, "String::isWellFormed", factory.unknown_boolean, |analyzer, _, _| {
+      // Strings with lone surrogates are not evaluated
+      Some(analyzer.factory.r#true)
+    }),
     "italics": factory.pure_fn_returns_string,
-    "lastIndexOf": factory.pure_fn_returns_unknown,
+    "lastIndexOf": string_method(factory, "String::lastIndexOf", factory.unknown_number, last_index_of),
     "link": factory.pure_fn_returns_string,
-    "localeCompare": factory.pure_fn_returns_unknown,
+    "localeCompare": factory.pure_fn_returns_number,
     "match": factory.pure_fn_returns_unknown,
     "matchAll": factory.pure_fn_returns_unknown,
     "normalize": factory.pure_fn_returns_string,
-    "padEnd": factory.pure_fn_returns_string,
-    "padStart": factory.pure_fn_returns_string,
-    "repeat": factory.pure_fn_returns_string,
-    "replace": factory.pure_fn_returns_unknown,
-    "replaceAll": factory.pure_fn_returns_unknown,
-    "search": factory.pure_fn_returns_unknown,
-    "slice": factory.pure_fn_returns_string,
+    "padEnd": string_method(factory, "String::padEnd", factory.unknown_string, |analyzer, value, args| {
+      pad(analyzer, value, args, false)
+    }),
+    "padStart": string_method(factory, "String::padStart", factory.unknown_string, |analyzer, value, args| {
+      pad(analyzer, value, args, true)
+    }),
+    "repeat": string_method(factory, "String::repeat", factory.unknown_string, repeat),
+    "replace": string_method(factory, "String::replace", factory.unknown_string, |analyzer, value, args| {
+      replace(analyzer, value, args, false)
+    }),
+    "replaceAll": string_method(factory, "String::replaceAll", factory.unknown_string, |analyzer, value, args| {
+      replace(analyzer, value, args, true)
+    }),
+    "search": factory.pure_fn_returns_number,
+    "slice": string_method(factory, "String::slice", factory.unknown_string, slice),
     "small": factory.pure_fn_returns_string,
-    "split": factory.pure_fn_returns_unknown,
-    "startsWith": factory.pure_fn_returns_unknown,
+    "split": string_split(factory),
+    "startsWith": string_method(factory, "String::startsWith", factory.unknown_boolean, |analyzer, value, args| {
+      let units = value.encode_utf16().collect::<Vec<_>>();
+      let search = string_arg(analyzer, args, 0)?.encode_utf16().collect::<Vec<_>>();
+      let start = resolve_index(number_arg(args, 1)?, units.len(), 0, false);
+      Some(analyzer.factory.boolean(units[start..].starts_with(&search)))
+    }),
     "strike": factory.pure_fn_returns_string,
     "sub": factory.pure_fn_returns_string,
-    "substr": factory.pure_fn_returns_string,
-    "substring": factory.pure_fn_returns_string,
+    "substr": string_method(factory, "String::substr", factory.unknown_string, substr),
+    "substring": string_method(factory, "String::substring", factory.unknown_string, substring),
     "sup": factory.pure_fn_returns_string,
     "toLocaleLowerCase": factory.pure_fn_returns_string,
     "toLocaleUpperCase": factory.pure_fn_returns_string,
-    "toLowerCase": factory.pure_fn_returns_string,
-    "toString": factory.pure_fn_returns_string,
-    "toUpperCase": factory.pure_fn_returns_string,
-    "trim": factory.pure_fn_returns_string,
-    "trimEnd": factory.pure_fn_returns_string,
-    "trimLeft": factory.pure_fn_returns_string,
-    "trimRight": factory.pure_fn_returns_string,
-    "trimStart": factory.pure_fn_returns_string,
-    "valueOf": factory.pure_fn_returns_string,
+    "toLowerCase": string_method(factory, "String::toLowerCase", factory.unknown_string, |analyzer, value, _| {
+      Some(analyzer.factory.unmangable_string(value.to_lowercase()))
+    }),
+    "toString": string_method(factory, "String::toString", factory.unknown_string, |analyzer, value, _| {
+      Some(analyzer.factory.unmangable_string(value))
+    }),
+    "toUpperCase": string_method(factory, "String::toUpperCase", factory.unknown_string, |analyzer, value, _| {
+      Some(analyzer.factory.unmangable_string(value.to_uppercase()))
+    }),
+    "toWellFormed": string_method(factory, "String::toWellFormed", factory.unknown_string, |analyzer, value, _| {
+      Some(analyzer.factory.unmangable_string(value))
+    }),
+    "trim": string_method(factory, "String::trim", factory.unknown_string, |analyzer, value, _| {
+      Some(analyzer.factory.unmangable_string(trim(value, true, true)))
+    }),
+    "trimEnd": string_method(factory, "String::trimEnd", factory.unknown_string, |analyzer, value, _| {
+      Some(analyzer.factory.unmangable_string(trim(value, false, true)))
+    }),
+    "trimLeft": string_method(factory, "String::trimLeft", factory.unknown_string, |analyzer, value, _| {
+      Some(analyzer.factory.unmangable_string(trim(value, true, false)))
+    }),
+    "trimRight": string_method(factory, "String::trimRight", factory.unknown_string, |analyzer, value, _| {
+      Some(analyzer.factory.unmangable_string(trim(value, false, true)))
+    }),
+    "trimStart": string_method(factory, "String::trimStart", factory.unknown_string, |analyzer, value, _| {
+      Some(analyzer.factory.unmangable_string(trim(value, true, false)))
+    }),
+    "valueOf": string_method(factory, "String::valueOf", factory.unknown_string, |analyzer, value, _| {
+      Some(analyzer.factory.unmangable_string(value))
+    }),
   })
 }
@@ -1,3 +1,4 @@
+use oxc::span::SourceType;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
@@ -12,10 +13,21 @@ impl TreeShakeJsxPreset {
   }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeShakeSourceKind {
+  Module,
+  /// Classic scripts, whose top-level bindings are properties of the global object
+  Script,
+  /// Module if the file contains import/export statements, otherwise script
+  Auto,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TreeShakeConfig {
   pub enabled: bool,
   pub jsx: TreeShakeJsxPreset,
+  /// Used when `Vfs::source_type` returns `None`
+  pub source_kind: TreeShakeSourceKind,
 
   pub max_recursion_depth: usize,
   pub remember_exhausted_variables: bool,
@@ -40,6 +52,9 @@ pub struct TreeShakeConfig {
   pub preserve_property_attributes: bool, // enumerable, configurable, writable
   pub impure_json_stringify: bool,
   pub precise_dynamic_prototype: bool,
+  /// Expand `import(`./dir/${name}.js`)` to all the matching files. Off by default, because
+  /// all the matching files are loaded, which requires `Vfs::read_dir`
+  pub glob_dynamic_import: bool,
 }
 
 impl Default for TreeShakeConfig {
@@ -53,6 +68,7 @@ impl TreeShakeConfig {
     Self {
       enabled: true,
       jsx: TreeShakeJsxPreset::None,
+      source_kind: TreeShakeSourceKind::Module,
 
       max_recursion_depth: 2,
       remember_exhausted_variables: true,
@@ -77,6 +93,7 @@ impl TreeShakeConfig {
       preserve_property_attributes: true,
       impure_json_stringify: true,
       precise_dynamic_prototype: true,
+      glob_dynamic_import: false,
     }
   }
 
@@ -108,6 +125,15 @@ impl TreeShakeConfig {
     Self { enabled: false, ..Self::default() }
   }
 
+  pub fn default_source_type(&self) -> SourceType {
+    let source_type = SourceType::mjs().with_jsx(self.jsx.is_enabled());
+    match self.source_kind {
+      TreeShakeSourceKind::Module => source_type,
+      TreeShakeSourceKind::Script => source_type.with_script(true),
+      TreeShakeSourceKind::Auto => source_type.with_unambiguous(true),
+    }
+  }
+
   pub fn normalize(&mut self) {
     if !self.advanced {
       self.folding = false;
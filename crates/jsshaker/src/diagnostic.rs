@@ -0,0 +1,167 @@
+use std::fmt;
+
+use line_index::LineIndex;
+use oxc::span::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DiagnosticSeverity {
+  /// The module can't be analyzed correctly, the output may be broken
+  Error,
+  /// The code may behave differently from what the analyzer assumes
+  Warning,
+  /// The analysis is less precise than usual, but the output is still correct
+  Info,
+}
+
+impl DiagnosticSeverity {
+  pub fn as_str(self) -> &'static str {
+    match self {
+      DiagnosticSeverity::Error => "error",
+      DiagnosticSeverity::Warning => "warning",
+      DiagnosticSeverity::Info => "info",
+    }
+  }
+}
+
+/// Stable identifiers of the diagnostics, which can be used to filter them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DiagnosticCode {
+  /// Syntax errors, including invalid JSON modules
+  ParseError,
+  /// TypeScript syntax that can't be erased
+  UnsupportedSyntax,
+  /// The module can't be resolved or read
+  UnresolvedModule,
+  /// An export listed for an entry doesn't exist
+  MissingExport,
+  /// The provided input source map can't be parsed
+  InvalidSourceMap,
+  /// The package.json has invalid fields, e.g. `sideEffects` patterns
+  InvalidPackageJson,
+  /// A builtin operation which always throws at runtime
+  BuiltinThrow,
+  /// Mutating builtin objects, which the analyzer assumes to be untouched
+  BuiltinMutation,
+  /// The recursion depth limit is reached, the function body is included as a whole
+  RecursionLimit,
+}
+
+impl DiagnosticCode {
+  pub fn as_str(self) -> &'static str {
+    match self {
+      DiagnosticCode::ParseError => "parse-error",
+      DiagnosticCode::UnsupportedSyntax => "unsupported-syntax",
+      DiagnosticCode::UnresolvedModule => "unresolved-module",
+      DiagnosticCode::MissingExport => "missing-export",
+      DiagnosticCode::InvalidSourceMap => "invalid-source-map",
+      DiagnosticCode::InvalidPackageJson => "invalid-package-json",
+      DiagnosticCode::BuiltinThrow => "builtin-throw",
+      DiagnosticCode::BuiltinMutation => "builtin-mutation",
+      DiagnosticCode::RecursionLimit => "recursion-limit",
+    }
+  }
+
+  pub fn severity(self) -> DiagnosticSeverity {
+    match self {
+      DiagnosticCode::ParseError
+      | DiagnosticCode::UnsupportedSyntax
+      | DiagnosticCode::UnresolvedModule
+      | DiagnosticCode::MissingExport => DiagnosticSeverity::Error,
+      DiagnosticCode::InvalidSourceMap
+      | DiagnosticCode::InvalidPackageJson
+      | DiagnosticCode::BuiltinThrow
+      | DiagnosticCode::BuiltinMutation => DiagnosticSeverity::Warning,
+      DiagnosticCode::RecursionLimit => DiagnosticSeverity::Info,
+    }
+  }
+}
+
+/// Lines and columns are 1-based, offsets are byte offsets in the source text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DiagnosticLocation {
+  pub start: u32,
+  pub end: u32,
+  pub start_line: u32,
+  pub start_column: u32,
+  pub end_line: u32,
+  pub end_column: u32,
+}
+
+impl DiagnosticLocation {
+  pub fn new(span: Span, line_index: &LineIndex) -> Self {
+    let start = line_index.line_col(span.start.into());
+    let end = line_index.line_col(span.end.into());
+    DiagnosticLocation {
+      start: span.start,
+      end: span.end,
+      start_line: start.line + 1,
+      start_column: start.col + 1,
+      end_line: end.line + 1,
+      end_column: end.col + 1,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Diagnostic {
+  pub severity: DiagnosticSeverity,
+  pub code: DiagnosticCode,
+  pub message: String,
+  /// The module which the diagnostic belongs to
+  pub path: Option<String>,
+  pub location: Option<DiagnosticLocation>,
+}
+
+impl Diagnostic {
+  pub fn new(code: DiagnosticCode, message: impl Into<String>) -> Self {
+    Diagnostic {
+      severity: code.severity(),
+      code,
+      message: message.into(),
+      path: None,
+      location: None,
+    }
+  }
+
+  pub fn with_path(mut self, path: impl Into<String>) -> Self {
+    self.path = Some(path.into());
+    self
+  }
+
+  pub fn with_span(mut self, span: Span, line_index: &LineIndex) -> Self {
+    self.location = Some(DiagnosticLocation::new(span, line_index));
+    self
+  }
+
+  /// Uses the first label of the parser error as the span
+  pub fn from_oxc(
+    code: DiagnosticCode,
+    error: &oxc::diagnostics::OxcDiagnostic,
+    path: &str,
+    line_index: &LineIndex,
+  ) -> Self {
+    let diagnostic = Diagnostic::new(code, error.message.to_string()).with_path(path);
+    match error.labels.as_ref().and_then(|labels| labels.first()) {
+      Some(label) => {
+        let start = label.offset() as u32;
+        diagnostic.with_span(Span::new(start, start + label.len() as u32), line_index)
+      }
+      None => diagnostic,
+    }
+  }
+}
+
+impl fmt::Display for Diagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}[{}]: {}", self.severity.as_str(), self.code.as_str(), self.message)?;
+    match (&self.path, &self.location) {
+      (Some(path), Some(location)) => write!(
+        f,
+        " at {}:{}:{}-{}:{}",
+        path, location.start_line, location.start_column, location.end_line, location.end_column
+      ),
+      (Some(path), None) => write!(f, " in {path}"),
+      _ => Ok(()),
+    }
+  }
+}
@@ -5,7 +5,8 @@ use crate::{
   value::{
     AbstractIterator, ArgumentsValue, EnumeratedProperties, IteratedElements, LiteralValue,
     ObjectPrototype, ObjectValue, TypeofResult, UnionHint, Value, ValueTrait, cacheable::Cacheable,
-    literal::PossibleLiterals,
+    collection::CollectionValue, generator::GeneratorValue, literal::PossibleLiterals,
+    promise::PromiseValue,
   },
 };
 
@@ -199,6 +200,15 @@ impl<'a> Entity<'a> {
   pub fn as_object(&self) -> Option<&'a ObjectValue<'a>> {
     self.value.as_object()
   }
+  pub fn as_collection(&self) -> Option<&'a CollectionValue<'a>> {
+    self.value.as_collection()
+  }
+  pub fn as_promise(&self) -> Option<&'a PromiseValue<'a>> {
+    self.value.as_promise()
+  }
+  pub fn as_generator(&self) -> Option<&'a GeneratorValue<'a>> {
+    self.value.as_generator()
+  }
   pub fn test_typeof(&self) -> TypeofResult {
     self.value.test_typeof()
   }
@@ -163,11 +163,13 @@ pub fn maybe_foldable_expr(node: &Expression) -> bool {
 
     Expression::JSXElement(_) | Expression::JSXFragment(_) => true,
 
-    Expression::V8IntrinsicExpression(_)
-    | Expression::TSAsExpression(_)
+    // Same as parenthesized expressions, the inner expression is folded instead
+    Expression::TSAsExpression(_)
     | Expression::TSInstantiationExpression(_)
     | Expression::TSTypeAssertion(_)
     | Expression::TSNonNullExpression(_)
-    | Expression::TSSatisfiesExpression(_) => unreachable!(),
+    | Expression::TSSatisfiesExpression(_) => false,
+
+    Expression::V8IntrinsicExpression(_) => unreachable!(),
   }
 }
@@ -0,0 +1,112 @@
+use oxc::ast::ast::{
+  ArrayExpressionElement, Expression, ObjectPropertyKind, Program, PropertyKey, Statement,
+  UnaryOperator, WithClause,
+};
+use serde_json::{Map, Number, Value};
+
+/// `with { type: "json" }`
+fn is_json_import(with_clause: Option<&WithClause>) -> bool {
+  with_clause.is_some_and(|with_clause| {
+    with_clause
+      .with_entries
+      .iter()
+      .any(|entry| entry.key.as_atom() == "type" && entry.value.value == "json")
+  })
+}
+
+/// Collects the specifiers imported with `with { type: "json" }`
+pub fn collect_json_imports<'a>(program: &Program<'a>) -> Vec<&'a str> {
+  let mut specifiers = vec![];
+  for node in &program.body {
+    let (source, with_clause) = match node {
+      Statement::ImportDeclaration(node) => (&node.source, &node.with_clause),
+      Statement::ExportAllDeclaration(node) => (&node.source, &node.with_clause),
+      Statement::ExportNamedDeclaration(node) => match &node.source {
+        Some(source) => (source, &node.with_clause),
+        None => continue,
+      },
+      _ => continue,
+    };
+    if is_json_import(with_clause.as_deref()) {
+      specifiers.push(source.value.as_str());
+    }
+  }
+  specifiers
+}
+
+/// A JSON module is analyzed as a JS module which default exports the JSON value
+pub fn json_to_module_source(json: &str) -> Result<String, String> {
+  serde_json::from_str::<serde::de::IgnoredAny>(json).map_err(|error| error.to_string())?;
+  Ok(format!("export default {json};"))
+}
+
+/// Converts the transformed module back to JSON
+pub fn module_to_json(program: &Program, minify: bool) -> String {
+  let value = program
+    .body
+    .iter()
+    .find_map(|node| match node {
+      Statement::ExportDefaultDeclaration(node) => node.declaration.as_expression(),
+      _ => None,
+    })
+    .map_or(Value::Null, expression_to_json);
+  let json =
+    if minify { serde_json::to_string(&value) } else { serde_json::to_string_pretty(&value) };
+  json.unwrap()
+}
+
+fn expression_to_json(node: &Expression) -> Value {
+  match node {
+    Expression::ObjectExpression(node) => {
+      let mut map = Map::new();
+      for property in &node.properties {
+        // Spread can't appear in JSON
+        let ObjectPropertyKind::ObjectProperty(property) = property else { continue };
+        let key = match &property.key {
+          PropertyKey::StaticIdentifier(key) => key.name.to_string(),
+          PropertyKey::StringLiteral(key) => key.value.to_string(),
+          PropertyKey::NumericLiteral(key) => number_to_json(key.value).to_string(),
+          _ => continue,
+        };
+        map.insert(key, expression_to_json(&property.value));
+      }
+      Value::Object(map)
+    }
+    Expression::ArrayExpression(node) => Value::Array(
+      node
+        .elements
+        .iter()
+        .map(|element| match element {
+          ArrayExpressionElement::SpreadElement(_) | ArrayExpressionElement::Elision(_) => {
+            Value::Null
+          }
+          element => expression_to_json(element.to_expression()),
+        })
+        .collect(),
+    ),
+    Expression::StringLiteral(node) => Value::String(node.value.to_string()),
+    Expression::NumericLiteral(node) => number_to_json(node.value),
+    Expression::BooleanLiteral(node) => Value::Bool(node.value),
+    Expression::ParenthesizedExpression(node) => expression_to_json(&node.expression),
+    Expression::UnaryExpression(node) => match (node.operator, &node.argument) {
+      (UnaryOperator::UnaryNegation, Expression::NumericLiteral(argument)) => {
+        number_to_json(-argument.value)
+      }
+      // Minified booleans
+      (UnaryOperator::LogicalNot, Expression::NumericLiteral(argument)) => {
+        Value::Bool(argument.value == 0.0)
+      }
+      _ => Value::Null,
+    },
+    // Includes `null`, and `void 0` for removed values
+    _ => Value::Null,
+  }
+}
+
+fn number_to_json(value: f64) -> Value {
+  if value.fract() == 0.0 && value.abs() < (1u64 << 53) as f64 {
+    Value::Number(Number::from(value as i64))
+  } else {
+    Number::from_f64(value).map_or(Value::Null, Value::Number)
+  }
+}
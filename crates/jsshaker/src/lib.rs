@@ -2,13 +2,19 @@ mod analyzer;
 mod builtins;
 mod config;
 mod dep;
+mod diagnostic;
 mod entity;
 mod folding;
+mod json;
 mod mangling;
 mod module;
 mod nodes;
+mod preparse;
 mod scope;
+mod session;
+mod source_map;
 mod transformer;
+mod typescript;
 mod utils;
 mod value;
 pub mod vfs;
@@ -16,7 +22,9 @@ pub mod vfs;
 use std::{cell::RefCell, collections::BTreeSet, mem, rc::Rc};
 
 pub use analyzer::Analyzer;
-pub use config::{TreeShakeConfig, TreeShakeJsxPreset};
+pub use config::{TreeShakeConfig, TreeShakeJsxPreset, TreeShakeSourceKind};
+pub use diagnostic::{Diagnostic, DiagnosticCode, DiagnosticLocation, DiagnosticSeverity};
+use line_index::LineIndex;
 use mangling::ManglerTransformer;
 pub use mangling::ManglingStats;
 use module::ModuleInfo;
@@ -29,7 +37,10 @@ use oxc::{
   span::SourceType,
 };
 use oxc_ast_visit::VisitMut;
+use oxc_sourcemap::SourceMap;
+use preparse::{AllocatorPool, ModuleCache};
 use rustc_hash::FxHashMap;
+pub use session::JsShakerSession;
 use transformer::Transformer;
 use utils::ast;
 pub use value::FnStats;
@@ -37,36 +48,93 @@ use vfs::Vfs;
 
 use crate::vfs::normalize_path;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryExports {
+  /// All exports of the entry are used
+  All,
+  /// Only the listed exports (`"default"` for the default export) are used
+  Only(Vec<String>),
+  /// Only the side effects of the entry are preserved
+  SideEffectsOnly,
+}
+
+#[derive(Debug, Clone)]
+pub struct JsShakerEntry {
+  pub path: String,
+  pub exports: EntryExports,
+}
+
+impl JsShakerEntry {
+  pub fn new(path: impl Into<String>) -> Self {
+    Self { path: path.into(), exports: EntryExports::All }
+  }
+
+  pub fn with_exports(path: impl Into<String>, exports: Vec<String>) -> Self {
+    Self { path: path.into(), exports: EntryExports::Only(exports) }
+  }
+
+  pub fn side_effects_only(path: impl Into<String>) -> Self {
+    Self { path: path.into(), exports: EntryExports::SideEffectsOnly }
+  }
+}
+
 pub struct JsShakerOptions<F: Vfs> {
   pub vfs: F,
+  /// All entries are analyzed together, so shared modules are shaken against all of their uses
+  pub entries: Vec<JsShakerEntry>,
   pub config: TreeShakeConfig,
   pub minify_options: Option<MinifierOptions>,
   pub codegen_options: CodegenOptions,
+  /// Also composed with the source maps provided by `Vfs::read_source_map`
   pub source_map: bool,
 }
 
 pub struct JsShakerReturn {
   pub codegen_return: FxHashMap<String, CodegenReturn>,
-  pub diagnostics: BTreeSet<String>,
+  pub diagnostics: BTreeSet<Diagnostic>,
   pub fn_stats: Option<FnStats>,
   pub mangling_stats: Option<mangling::ManglingStats>,
 }
 
-pub fn tree_shake<F: Vfs + 'static>(options: JsShakerOptions<F>, entry: String) -> JsShakerReturn {
-  let JsShakerOptions { vfs, config, minify_options, codegen_options, source_map } = options;
+pub fn tree_shake<F: Vfs + 'static>(options: JsShakerOptions<F>) -> JsShakerReturn {
+  tree_shake_with_cache(options, None)
+}
+
+fn tree_shake_with_cache<F: Vfs + 'static>(
+  options: JsShakerOptions<F>,
+  cache: Option<&mut ModuleCache>,
+) -> JsShakerReturn {
+  let JsShakerOptions { vfs, entries, config, minify_options, codegen_options, source_map } =
+    options;
 
   if config.enabled {
+    // Mangling only mutates the parsed programs in place, which can't be reused
+    let cache = cache.filter(|_| config.mangling != Some(true));
+    let module_allocators = AllocatorPool::default();
     let allocator = Allocator::default();
     let config = allocator.alloc(config);
     config.normalize();
 
     // Step 1: Analyze
     let mut analyzer = Analyzer::new_in(Box::new(vfs), config, &allocator);
-    let module_id = analyzer.parse_module(normalize_path::normalize_str(&entry));
-    analyzer.exec_module(module_id);
-    analyzer.post_analysis();
+    analyzer.modules.cache = cache;
+    analyzer.preparse_modules(
+      entries.iter().map(|entry| normalize_path::normalize_str(&entry.path)).collect(),
+      &module_allocators,
+    );
+    let entries = entries
+      .into_iter()
+      .filter_map(|entry| {
+        Some((analyzer.parse_module(normalize_path::normalize_str(&entry.path))?, entry.exports))
+      })
+      .collect::<Vec<_>>();
+    for (module_id, _) in &entries {
+      analyzer.exec_module(*module_id);
+    }
+    analyzer.post_analysis(&entries);
 
     let Analyzer {
+      vfs,
       modules,
       diagnostics,
       folder,
@@ -80,7 +148,13 @@ pub fn tree_shake<F: Vfs + 'static>(options: JsShakerOptions<F>, entry: String)
     let mut codegen_return = FxHashMap::default();
     let mangling_stats = config.enable_mangling_stats.then(Default::default);
     for module_info in mem::take(&mut modules.modules) {
-      let ModuleInfo { path, program, semantic, .. } = module_info;
+      let ModuleInfo { path, program, mut parsed, is_json, .. } = module_info;
+      // Mangling only mutates the program in place. The module is not cached in this mode, so it
+      // is only owned here, and the analysis data only keeps the addresses of the nodes.
+      let program_mut = (config.mangling == Some(true)).then(|| {
+        let parsed = Rc::get_mut(&mut parsed).expect("Modules are not cached when mangling only");
+        unsafe { parsed.program_mut() }
+      });
 
       // Step 2: Transform
       let transformer = Transformer::new(
@@ -92,16 +166,15 @@ pub fn tree_shake<F: Vfs + 'static>(options: JsShakerOptions<F>, entry: String)
         conditional_data,
         folder,
         mangler.clone(),
-        semantic,
+        parsed,
         mangling_stats.clone(),
       );
-      let program = unsafe { &mut *program.get() };
-      let program = if config.mangling == Some(true) {
-        // Mangling only
-        ManglerTransformer(transformer).visit_program(program);
-        program
-      } else {
-        allocator.alloc(transformer.transform_program(program))
+      let program = match program_mut {
+        Some(program) => {
+          ManglerTransformer(transformer).visit_program(program);
+          program
+        }
+        None => allocator.alloc(transformer.transform_program(program)),
       };
 
       // Step 3: Minify
@@ -117,7 +190,35 @@ pub fn tree_shake<F: Vfs + 'static>(options: JsShakerOptions<F>, entry: String)
           ..codegen_options.clone()
         })
         .with_scoping(minifier_return.and_then(|r| r.scoping));
-      codegen_return.insert(path.to_string(), codegen.build(program));
+      let mut output = codegen.build(program);
+      if is_json {
+        output.code = json::module_to_json(program, codegen_options.minify);
+        output.map = None;
+      }
+      if let Some(map) = &output.map
+        && let Some(input_map) = vfs.read_source_map(&path)
+      {
+        match SourceMap::from_json_string(&input_map) {
+          Ok(input_map) => output.map = Some(source_map::compose_source_maps(map, &input_map)),
+          Err(error) => {
+            diagnostics.insert(
+              Diagnostic::new(
+                DiagnosticCode::InvalidSourceMap,
+                format!("Invalid source map: {error}"),
+              )
+              .with_path(path.as_str()),
+            );
+          }
+        }
+      }
+      codegen_return.insert(path.to_string(), output);
+    }
+    // `CodegenReturn` can only be created by `Codegen`
+    let empty_program = Parser::new(&allocator, "", SourceType::mjs()).parse().program;
+    for (path, code) in mem::take(&mut modules.unparsed_sources) {
+      let mut output = Codegen::new().build(&empty_program);
+      output.code = code;
+      codegen_return.insert(path, output);
     }
     JsShakerReturn {
       codegen_return,
@@ -130,23 +231,62 @@ pub fn tree_shake<F: Vfs + 'static>(options: JsShakerOptions<F>, entry: String)
     let config = allocator.alloc(config);
     config.normalize();
 
-    let source_text = vfs.read_file(&entry);
-    let parser =
-      Parser::new(&allocator, &source_text, SourceType::mjs().with_jsx(config.jsx.is_enabled()));
-    let parsed = parser.parse();
-    let mut program = parsed.program;
-    let minifier_return = minify_options.map(|options| {
-      let minifier = Minifier::new(options);
-      minifier.dce(&allocator, &mut program)
-    });
-    let codegen = Codegen::new()
-      .with_options(codegen_options.clone())
-      .with_scoping(minifier_return.and_then(|r| r.scoping));
     let mut codegen_return = FxHashMap::default();
-    codegen_return.insert(entry, codegen.build(&program));
-    let mut diagnostics = BTreeSet::<String>::default();
-    for error in parsed.errors {
-      diagnostics.insert(error.to_string());
+    let mut diagnostics = BTreeSet::<Diagnostic>::default();
+    for JsShakerEntry { path, .. } in entries {
+      let source_text = match vfs.read_file(&path) {
+        Ok(source_text) => source_text,
+        Err(error) => {
+          diagnostics.insert(Diagnostic::new(
+            DiagnosticCode::UnresolvedModule,
+            format!("Cannot read module {path}: {error}"),
+          ));
+          continue;
+        }
+      };
+      let source_type = vfs.source_type(&path).unwrap_or_else(|| config.default_source_type());
+      let line_index = LineIndex::new(&source_text);
+      let parser = Parser::new(&allocator, &source_text, source_type);
+      let parsed = parser.parse();
+      let mut program = parsed.program;
+      for error in parsed.errors {
+        diagnostics.insert(Diagnostic::from_oxc(
+          DiagnosticCode::ParseError,
+          &error,
+          &path,
+          &line_index,
+        ));
+      }
+      if source_type.is_typescript() {
+        let (typescript_errors, is_unsupported) =
+          match typescript::strip_typescript(&allocator, &mut program) {
+            Ok(errors) => (errors, false),
+            Err(errors) => (errors, true),
+          };
+        for (span, message) in typescript_errors {
+          diagnostics.insert(
+            Diagnostic::new(DiagnosticCode::UnsupportedSyntax, message)
+              .with_path(path.as_str())
+              .with_span(span, &line_index),
+          );
+        }
+        if is_unsupported {
+          // Left untransformed
+          let empty_program = Parser::new(&allocator, "", SourceType::mjs()).parse().program;
+          let mut output = Codegen::new().build(&empty_program);
+          output.code = source_text;
+          codegen_return.insert(path, output);
+          continue;
+        }
+      }
+      let minifier_return = minify_options.clone().map(|options| {
+        let minifier = Minifier::new(options);
+        minifier.dce(&allocator, &mut program)
+      });
+      let codegen = Codegen::new()
+        .with_options(codegen_options.clone())
+        .with_scoping(minifier_return.and_then(|r| r.scoping));
+      codegen_return.insert(path, codegen.build(&program));
     }
     JsShakerReturn { codegen_return, diagnostics, fn_stats: None, mangling_stats: None }
   }
@@ -3,7 +3,8 @@ use std::{fs::File, io::Write, path::PathBuf};
 use clap::Parser;
 use flate2::{Compression, write::GzEncoder};
 use jsshaker::{
-  JsShakerOptions, TreeShakeConfig, TreeShakeJsxPreset, tree_shake,
+  JsShakerEntry, JsShakerOptions, TreeShakeConfig, TreeShakeJsxPreset, TreeShakeSourceKind,
+  tree_shake,
   vfs::{SingleFileFs, StdFs, Vfs},
 };
 use oxc::{
@@ -16,6 +17,14 @@ use oxc::{
 struct Args {
   path: String,
 
+  #[arg(short, long)]
+  // Additional entries, only for multi-file mode
+  entry: Vec<String>,
+
+  #[arg(long, value_delimiter = ',')]
+  // Exports of `path` to keep, all by default
+  exports: Option<Vec<String>>,
+
   #[arg(short, long, default_value_t = false)]
   single_file: bool,
 
@@ -34,6 +43,10 @@ struct Args {
   #[arg(short, long, default_value_t = true)]
   jsx: bool,
 
+  #[arg(long, default_value_t = String::from("module"))]
+  // module/script/auto
+  source_kind: String,
+
   #[arg(short, long, default_value_t = false)]
   advanced: bool,
 
@@ -45,6 +58,10 @@ struct Args {
   // on/off/only
   mangle: String,
 
+  #[arg(long, default_value_t = false)]
+  // Expand `import(`./dir/${name}.js`)` to all the matching files
+  glob_dynamic_import: bool,
+
   #[arg(short, long, default_value_t = 2)]
   recursion_depth: usize,
 
@@ -62,9 +79,19 @@ fn main() {
   let args = Args::parse();
 
   let jsx = if args.jsx { TreeShakeJsxPreset::React } else { TreeShakeJsxPreset::None };
-  let shake_disabled = TreeShakeConfig { jsx, ..TreeShakeConfig::disabled() };
+  let source_kind = match args.source_kind.as_str() {
+    "module" => TreeShakeSourceKind::Module,
+    "script" => TreeShakeSourceKind::Script,
+    "auto" => TreeShakeSourceKind::Auto,
+    _ => {
+      eprintln!("Invalid --source-kind: {}", args.source_kind);
+      std::process::exit(1);
+    }
+  };
+  let shake_disabled = TreeShakeConfig { jsx, source_kind, ..TreeShakeConfig::disabled() };
   let shake_enabled = TreeShakeConfig {
     jsx,
+    source_kind,
     advanced: args.advanced,
     folding: match args.folding.as_str() {
       "on" => true,
@@ -89,6 +116,7 @@ fn main() {
         std::process::exit(1);
       }
     },
+    glob_dynamic_import: args.glob_dynamic_import,
     max_recursion_depth: args.recursion_depth,
     remember_exhausted_variables: !args.no_remember_exhausted,
     enable_fn_cache: !args.no_fn_cache,
@@ -104,6 +132,11 @@ fn main() {
     }
   };
 
+  let main_entry = |path: &str| match &args.exports {
+    Some(exports) => JsShakerEntry::with_exports(path, exports.clone()),
+    None => JsShakerEntry::new(path),
+  };
+
   let minify_options = MinifierOptions {
     mangle: Some(MangleOptions { top_level: true, ..Default::default() }),
     ..Default::default()
@@ -122,49 +155,41 @@ fn main() {
 
     let start_time = std::time::Instant::now();
 
-    let copied = tree_shake(
-      JsShakerOptions {
-        vfs: SingleFileFs(source.clone()),
-        config: shake_disabled.clone(),
-        minify_options: None,
-        codegen_options: CodegenOptions::default(),
-        source_map: false,
-      },
-      SingleFileFs::ENTRY_PATH.to_string(),
-    );
+    let copied = tree_shake(JsShakerOptions {
+      vfs: SingleFileFs(source.clone()),
+      entries: vec![JsShakerEntry::new(SingleFileFs::ENTRY_PATH)],
+      config: shake_disabled.clone(),
+      minify_options: None,
+      codegen_options: CodegenOptions::default(),
+      source_map: false,
+    });
     let copied_code = copied.codegen_return[SingleFileFs::ENTRY_PATH].code.clone();
-    let minified = tree_shake(
-      JsShakerOptions {
-        vfs: SingleFileFs(source.clone()),
-        config: shake_disabled.clone(),
-        minify_options: Some(minify_options.clone()),
-        codegen_options: min_codegen_options.clone(),
-        source_map: false,
-      },
-      SingleFileFs::ENTRY_PATH.to_string(),
-    );
+    let minified = tree_shake(JsShakerOptions {
+      vfs: SingleFileFs(source.clone()),
+      entries: vec![JsShakerEntry::new(SingleFileFs::ENTRY_PATH)],
+      config: shake_disabled.clone(),
+      minify_options: Some(minify_options.clone()),
+      codegen_options: min_codegen_options.clone(),
+      source_map: false,
+    });
     let minified_code = minified.codegen_return[SingleFileFs::ENTRY_PATH].code.clone();
-    let shaken = tree_shake(
-      JsShakerOptions {
-        vfs: SingleFileFs(source.clone()),
-        config: shake_enabled,
-        minify_options: None,
-        codegen_options: CodegenOptions::default(),
-        source_map: false,
-      },
-      SingleFileFs::ENTRY_PATH.to_string(),
-    );
+    let shaken = tree_shake(JsShakerOptions {
+      vfs: SingleFileFs(source.clone()),
+      entries: vec![main_entry(SingleFileFs::ENTRY_PATH)],
+      config: shake_enabled,
+      minify_options: None,
+      codegen_options: CodegenOptions::default(),
+      source_map: false,
+    });
     let shaken_code = shaken.codegen_return[SingleFileFs::ENTRY_PATH].code.clone();
-    let shaken_minified = tree_shake(
-      JsShakerOptions {
-        vfs: SingleFileFs(shaken_code.clone()),
-        config: shake_disabled.clone(),
-        minify_options: Some(minify_options.clone()),
-        codegen_options: min_codegen_options,
-        source_map: false,
-      },
-      SingleFileFs::ENTRY_PATH.to_string(),
-    );
+    let shaken_minified = tree_shake(JsShakerOptions {
+      vfs: SingleFileFs(shaken_code.clone()),
+      entries: vec![JsShakerEntry::new(SingleFileFs::ENTRY_PATH)],
+      config: shake_disabled.clone(),
+      minify_options: Some(minify_options.clone()),
+      codegen_options: min_codegen_options,
+      source_map: false,
+    });
     let shaken_minified_code =
       shaken_minified.codegen_return[SingleFileFs::ENTRY_PATH].code.clone();
 
@@ -230,20 +255,20 @@ fn main() {
   } else {
     let start_time = std::time::Instant::now();
 
-    let shaken = tree_shake(
-      JsShakerOptions {
-        vfs: StdFs,
-        config: shake_enabled,
-        minify_options: args.minify.then_some(minify_options.clone()),
-        codegen_options: if args.minify {
-          min_codegen_options.clone()
-        } else {
-          CodegenOptions::default()
-        },
-        source_map: false,
+    let shaken = tree_shake(JsShakerOptions {
+      vfs: StdFs,
+      entries: std::iter::once(main_entry(&args.path))
+        .chain(args.entry.iter().map(JsShakerEntry::new))
+        .collect(),
+      config: shake_enabled,
+      minify_options: args.minify.then_some(minify_options.clone()),
+      codegen_options: if args.minify {
+        min_codegen_options.clone()
+      } else {
+        CodegenOptions::default()
       },
-      args.path.clone(),
-    );
+      source_map: false,
+    });
 
     for diagnostic in shaken.diagnostics.iter() {
       eprintln!("{}", diagnostic);
@@ -283,22 +308,20 @@ fn main() {
       };
       output_file.write_all(codegen_return.code.as_bytes()).unwrap();
 
-      let source = StdFs.read_file(&path);
-
-      let non_shaken = tree_shake(
-        JsShakerOptions {
-          vfs: SingleFileFs(source.clone()),
-          config: shake_disabled.clone(),
-          minify_options: args.minify.then_some(minify_options.clone()),
-          codegen_options: if args.minify {
-            min_codegen_options.clone()
-          } else {
-            CodegenOptions::default()
-          },
-          source_map: false,
+      let source = StdFs.read_file(&path).unwrap();
+
+      let non_shaken = tree_shake(JsShakerOptions {
+        vfs: SingleFileFs(source.clone()),
+        entries: vec![JsShakerEntry::new(SingleFileFs::ENTRY_PATH)],
+        config: shake_disabled.clone(),
+        minify_options: args.minify.then_some(minify_options.clone()),
+        codegen_options: if args.minify {
+          min_codegen_options.clone()
+        } else {
+          CodegenOptions::default()
         },
-        SingleFileFs::ENTRY_PATH.to_string(),
-      );
+        source_map: false,
+      });
       let non_shaken_code = non_shaken.codegen_return[SingleFileFs::ENTRY_PATH].code.clone();
 
       let mut copy_file = match File::create(&copy_path) {
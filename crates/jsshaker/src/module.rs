@@ -1,11 +1,10 @@
-use std::{cell::UnsafeCell, mem};
+use std::{mem, rc::Rc};
 
 use line_index::LineIndex;
 use oxc::{
   allocator::FromIn,
   ast::ast::{ImportDeclaration, Program, Statement},
-  parser::Parser,
-  semantic::{Semantic, SemanticBuilder, SymbolId},
+  semantic::{ScopeFlags, Semantic, SymbolId},
   span::{Atom, SourceType},
 };
 use oxc_index::{IndexVec, define_index_type};
@@ -15,10 +14,13 @@ use crate::{
   analyzer::Analyzer,
   builtin_string,
   dep::{CustomDepTrait, DepAtom},
+  diagnostic::{Diagnostic, DiagnosticCode},
   entity::Entity,
+  json::{collect_json_imports, json_to_module_source},
+  preparse::{ModuleCache, ParsedModule, hash_source, parse_module_source},
   scope::{CfScopeKind, VariableScopeId, call_scope::CallScope, variable_scope::EntityOrTDZ},
-  utils::{CalleeInfo, CalleeNode, ast::AstKind2},
-  value::module_object::ModuleObjectValue,
+  utils::{CalleeInfo, CalleeNode, ast::AstKind2, find_top_level_await},
+  value::{LiteralValue, module_object::ModuleObjectValue},
 };
 
 #[derive(Debug, Clone, Copy)]
@@ -27,6 +29,8 @@ pub enum ExportedValue<'a> {
   Function(Entity<'a>, DepAtom),
   Namespace(Entity<'a>, DepAtom),
   ReExport(ModuleId, Atom<'a>, DepAtom),
+  /// A property of `module.exports` of a CommonJS module
+  CommonJs(Entity<'a>, Atom<'a>, DepAtom),
   Unknown(DepAtom),
 }
 
@@ -37,17 +41,27 @@ impl<'a> ExportedValue<'a> {
       | ExportedValue::Function(_, dep)
       | ExportedValue::Namespace(_, dep)
       | ExportedValue::ReExport(_, _, dep)
+      | ExportedValue::CommonJs(_, _, dep)
       | ExportedValue::Unknown(dep) => *dep,
     }
   }
 }
 
+/// The free variables available in a CommonJS module
+#[derive(Debug, Clone, Copy)]
+pub struct CommonJsModule<'a> {
+  pub module: Entity<'a>,
+  pub exports: Entity<'a>,
+  pub require: Entity<'a>,
+}
+
 pub struct ModuleInfo<'a> {
   pub id: ModuleId,
   pub path: Atom<'a>,
   pub line_index: LineIndex,
-  pub program: UnsafeCell<&'a mut Program<'a>>,
-  pub semantic: Semantic<'a>,
+  pub program: &'a Program<'a>,
+  /// Shared with the cache between runs, which owns the semantic data
+  pub parsed: Rc<ParsedModule<'a>>,
   pub callee: CalleeInfo<'a>,
   pub call_id: DepAtom,
 
@@ -62,6 +76,19 @@ pub struct ModuleInfo<'a> {
 
   pub import_meta: Entity<'a>,
   pub module_object: Entity<'a>,
+  pub is_commonjs: bool,
+  /// A classic script, whose top-level bindings are shared with other scripts via the global object
+  pub is_script: bool,
+  /// Identifiers in `with` bodies are resolved dynamically
+  pub has_with_statement: bool,
+  /// The index of the first top-level statement which contains `await`
+  pub top_level_await: Option<usize>,
+  /// Synthesized from a JSON file, which default exports the JSON value
+  pub is_json: bool,
+  /// `false` if the module is declared side-effect-free, e.g. via `"sideEffects"` in package.json
+  pub side_effects: bool,
+  /// Created when a CommonJS module starts executing
+  pub commonjs: Option<CommonJsModule<'a>>,
   pub initializing: bool,
   pub initialized: bool,
   pub circular_imports: Vec<(ModuleId, VariableScopeId, &'a ImportDeclaration<'a>)>,
@@ -76,6 +103,17 @@ define_index_type! {
 pub struct Modules<'a> {
   pub modules: IndexVec<ModuleId, ModuleInfo<'a>>,
   paths: FxHashMap<String, ModuleId>,
+  /// Parsed in parallel before the analysis, see `preparse_modules`
+  pub preparsed: FxHashMap<String, Rc<ParsedModule<'a>>>,
+  /// Parsed modules kept between runs of a session, including the lazily loaded ones
+  pub cache: Option<&'a mut ModuleCache>,
+  /// Bindings imported from modules which are not initialized yet, due to circular imports.
+  /// They are read from the exporting module until initialized.
+  pub live_imports: FxHashMap<(VariableScopeId, SymbolId), (ModuleId, Atom<'a>)>,
+  /// Sources of the modules which can't be parsed, e.g. invalid JSON files, or transformed,
+  /// e.g. TypeScript files with syntax that can't be erased.
+  /// They are treated as external, and emitted unchanged.
+  pub unparsed_sources: FxHashMap<String, String>,
 }
 
 impl<'a> Analyzer<'a> {
@@ -92,7 +130,7 @@ impl<'a> Analyzer<'a> {
   }
 
   pub fn semantic<'b>(&'b self) -> &'b Semantic<'a> {
-    &self.module_info().semantic
+    &self.module_info().parsed.semantic
   }
 
   pub fn line_index(&self) -> &LineIndex {
@@ -100,40 +138,148 @@ impl<'a> Analyzer<'a> {
   }
 
   pub fn is_readonly_symbol(&mut self, symbol_id: SymbolId) -> bool {
-    let ModuleInfo { readonly_symbol_cache, semantic, .. } = self.module_info_mut();
+    let ModuleInfo { readonly_symbol_cache, parsed, .. } = self.module_info_mut();
     *readonly_symbol_cache
       .entry(symbol_id)
-      .or_insert_with(|| !semantic.symbol_references(symbol_id).any(|r| r.is_write()))
+      .or_insert_with(|| !parsed.semantic.symbol_references(symbol_id).any(|r| r.is_write()))
   }
 
   pub fn resolve_and_parse_module(&mut self, specifier: &str) -> Option<ModuleId> {
+    self.resolve_and_parse_module_as(specifier, false)
+  }
+
+  /// Modules which can't be resolved or read are treated as external
+  fn resolve_and_parse_module_as(&mut self, specifier: &str, is_json: bool) -> Option<ModuleId> {
     let importer = &self.module_info().path;
-    let path = self.vfs.resolve_module(importer, specifier)?;
-    Some(self.parse_module(path))
+    match self.vfs.resolve_module(importer, specifier) {
+      Ok(path) => self.parse_module_as(path?, is_json),
+      Err(error) => {
+        self.add_diagnostic(DiagnosticCode::UnresolvedModule, error.to_string());
+        None
+      }
+    }
   }
 
-  pub fn parse_module(&mut self, path: String) -> ModuleId {
+  pub fn parse_module(&mut self, path: String) -> Option<ModuleId> {
+    self.parse_module_as(path, false)
+  }
+
+  /// `is_json` is `true` if the module is imported with `with { type: "json" }`
+  fn parse_module_as(&mut self, path: String, is_json: bool) -> Option<ModuleId> {
     if let Some(module_id) = self.modules.paths.get(path.as_str()) {
-      return *module_id;
-    }
-
-    let source_text = self.allocator.alloc_str(&self.vfs.read_file(path.as_str()));
-    let line_index = LineIndex::new(source_text);
-    let parser = Parser::new(
-      self.allocator,
-      source_text,
-      SourceType::mjs().with_jsx(self.config.jsx.is_enabled()),
-    );
-    let parsed = parser.parse();
-    let program_cell = UnsafeCell::new(self.allocator.alloc(parsed.program));
-    let program = unsafe { &mut *program_cell.get() };
-    for error in parsed.errors {
-      self.add_diagnostic(format!("[{}] {}", path, error));
-    }
-    let semantic = SemanticBuilder::new().build(program).semantic;
+      return Some(*module_id);
+    }
+    if self.modules.unparsed_sources.contains_key(&path) {
+      return None;
+    }
+
+    let is_json = is_json || path.ends_with(".json");
+    let preparsed = self.modules.preparsed.remove(&path).filter(|_| !is_json);
+    let cached = || self.modules.cache.as_mut()?.get(&path, None, is_json);
+    let parsed = if let Some(parsed) = preparsed.or_else(cached) {
+      parsed
+    } else {
+      let mut source_text = match self.vfs.read_file(path.as_str()) {
+        Ok(source_text) => source_text,
+        Err(error) => {
+          self.add_diagnostic(
+            DiagnosticCode::UnresolvedModule,
+            format!("Cannot read module {path}: {error}"),
+          );
+          return None;
+        }
+      };
+      let hash = hash_source(&source_text);
+      if let Some(parsed) =
+        self.modules.cache.as_mut().and_then(|cache| cache.get(&path, Some(hash), is_json))
+      {
+        parsed
+      } else {
+        if is_json {
+          source_text = match json_to_module_source(&source_text) {
+            Ok(module_source) => module_source,
+            Err(error) => {
+              self.diagnostics.insert(
+                Diagnostic::new(DiagnosticCode::ParseError, format!("Invalid JSON: {error}"))
+                  .with_path(path.as_str()),
+              );
+              self.modules.unparsed_sources.insert(path, source_text);
+              return None;
+            }
+          };
+        }
+        let source_type = if is_json {
+          SourceType::mjs()
+        } else {
+          self.vfs.source_type(path.as_str()).unwrap_or_else(|| self.config.default_source_type())
+        };
+        if let Some(cache) = &mut self.modules.cache {
+          cache.parse_and_insert(path.clone(), hash, is_json, &source_text, source_type)
+        } else {
+          let source_text = self.allocator.alloc_str(&source_text);
+          Rc::new(parse_module_source(self.allocator, source_text, source_type))
+        }
+      }
+    };
+    let ParsedModule {
+      source_type,
+      line_index,
+      module_record,
+      errors,
+      typescript_errors,
+      typescript_unsupported,
+      semantic,
+      ..
+    } = &*parsed;
+    let program = parsed.program();
+    let source_type = *source_type;
+    let line_index = line_index.clone();
+    for error in errors {
+      self.diagnostics.insert(Diagnostic::from_oxc(
+        DiagnosticCode::ParseError,
+        error,
+        &path,
+        &line_index,
+      ));
+    }
+    for (span, message) in typescript_errors {
+      self.diagnostics.insert(
+        Diagnostic::new(DiagnosticCode::UnsupportedSyntax, message.as_str())
+          .with_path(path.as_str())
+          .with_span(*span, &line_index),
+      );
+    }
+    if *typescript_unsupported {
+      self.modules.unparsed_sources.insert(path, program.source_text.to_string());
+      return None;
+    }
+    // Explicit scripts are not detected as CommonJS, e.g. UMD scripts which reference `module`
+    let is_commonjs = path.ends_with(".cjs")
+      || path.ends_with(".cts")
+      || !module_record.has_module_syntax
+        && !source_type.is_script()
+        && semantic
+          .scoping()
+          .root_unresolved_references()
+          .keys()
+          .any(|name| matches!(*name, "module" | "exports" | "require"));
+    let is_script = program.source_type.is_script() && !is_commonjs;
+    let has_with_statement = semantic
+      .scoping()
+      .scope_descendants_from_root()
+      .any(|scope| semantic.scoping().scope_flags(scope).contains(ScopeFlags::With));
+    let top_level_await = find_top_level_await(&program.body);
+    let side_effects = self.vfs.has_side_effects(&path).unwrap_or_else(|error| {
+      self.diagnostics.insert(
+        Diagnostic::new(DiagnosticCode::InvalidPackageJson, error.to_string())
+          .with_path(path.as_str()),
+      );
+      true
+    });
     let module_id = ModuleId::from_usize(self.modules.modules.len());
     let variable_scope = self.push_variable_scope();
-    self.variable_scope_mut().this = Some(self.factory.unknown);
+    self.variable_scope_mut().this =
+      Some(if is_script { self.builtins.globals["globalThis"] } else { self.factory.unknown });
     let import_meta = self.create_import_meta();
     let callee = CalleeInfo {
       module_id,
@@ -147,8 +293,8 @@ impl<'a> Analyzer<'a> {
       id: module_id,
       path: Atom::from_in(path.clone(), self.allocator),
       line_index,
-      program: program_cell,
-      semantic,
+      program,
+      parsed: parsed.clone(),
       callee,
       call_id: DepAtom::from_counter(),
       readonly_symbol_cache: Default::default(),
@@ -160,6 +306,13 @@ impl<'a> Analyzer<'a> {
       exports_included: false,
       import_meta,
       module_object: self.factory.alloc(ModuleObjectValue::new(module_id)).into(),
+      is_commonjs,
+      is_script,
+      has_with_statement,
+      top_level_await,
+      is_json,
+      side_effects,
+      commonjs: None,
       initializing: false,
       initialized: false,
       circular_imports: Default::default(),
@@ -168,8 +321,13 @@ impl<'a> Analyzer<'a> {
     self.modules.paths.insert(path.clone(), module_id);
 
     let old_module = self.set_current_module(module_id);
-    for specifier in parsed.module_record.requested_modules.keys() {
-      if let Some(id) = self.resolve_and_parse_module(specifier) {
+    let json_imports = collect_json_imports(program);
+    for (specifier, requests) in &module_record.requested_modules {
+      let is_json = json_imports.contains(&specifier.as_str());
+      self.span_stack.push(requests[0].span);
+      let resolved = self.resolve_and_parse_module_as(specifier, is_json);
+      self.span_stack.pop();
+      if let Some(id) = resolved {
         self.module_info_mut().resolved_imports.insert(*specifier, id);
       }
     }
@@ -187,12 +345,21 @@ impl<'a> Analyzer<'a> {
     for node in &program.body {
       self.declare_statement(node);
     }
+    if is_script {
+      // Other scripts may read or write them at any time
+      let root_scope = self.semantic().scoping().root_scope_id();
+      let symbols =
+        self.semantic().scoping().get_bindings(root_scope).values().copied().collect::<Vec<_>>();
+      for symbol in symbols {
+        self.include_on_scope(variable_scope, symbol);
+      }
+    }
     self.scoping.call.pop();
     self.pop_variable_scope();
     self.pop_cf_scope();
     self.set_current_module(old_module);
 
-    module_id
+    Some(module_id)
   }
 
   pub fn exec_module(&mut self, module_id: ModuleId) {
@@ -218,36 +385,52 @@ impl<'a> Analyzer<'a> {
       false,
     ));
 
+    if self.module_info().is_commonjs {
+      // Created here so that writes to `module.exports` are tracked in the module's scope
+      let commonjs = self.create_commonjs_module();
+      self.module_info_mut().commonjs = Some(commonjs);
+      // `this` is `module.exports` at the top level of a CommonJS module
+      self.variable_scope_mut().this = Some(commonjs.exports);
+    }
+
     // First pass: handle imports and exports
     // Use index-based iteration because recursive calls to exec_module can cause
     // self.modules to reallocate, invalidating any borrowed references to program.
     let body_len = {
       let module = &self.modules.modules[module_id];
-      let program = unsafe { &*module.program.get() };
+      let program = module.program;
       program.body.len()
     };
 
     for i in 0..body_len {
       let module = &self.modules.modules[module_id];
-      let program = unsafe { &*module.program.get() };
-      let node = &program.body[i];
-      match node {
+      let program = module.program;
+      let source = match &program.body[i] {
         Statement::ImportDeclaration(node) => {
-          self.init_import_declaration(node);
-        }
-        Statement::ExportAllDeclaration(node) => {
-          if let Some(resolved) = self.module_info().resolved_imports.get(&node.source.value) {
-            self.exec_module(*resolved);
-          }
-        }
-        Statement::ExportNamedDeclaration(node) => {
-          if let Some(source) = &node.source
-            && let Some(resolved) = self.module_info().resolved_imports.get(&source.value)
-          {
-            self.exec_module(*resolved);
+          if self.builtins.get_known_module(node.source.value.as_str()).is_some() {
+            continue;
           }
+          &node.source
         }
-        _ => {}
+        Statement::ExportAllDeclaration(node) => &node.source,
+        Statement::ExportNamedDeclaration(node) => match &node.source {
+          Some(source) => source,
+          None => continue,
+        },
+        _ => continue,
+      };
+      if let Some(resolved) = self.module_info().resolved_imports.get(&source.value) {
+        self.exec_module(*resolved);
+      }
+    }
+
+    // Bindings are initialized after all dependencies are executed,
+    // because later dependencies may write to the exports of earlier ones
+    for i in 0..body_len {
+      let module = &self.modules.modules[module_id];
+      let program = module.program;
+      if let Statement::ImportDeclaration(node) = &program.body[i] {
+        self.init_import_declaration(node);
       }
     }
 
@@ -255,17 +438,26 @@ impl<'a> Analyzer<'a> {
     // Re-access body length and iterate by index
     let body_len = {
       let module = &self.modules.modules[module_id];
-      let program = unsafe { &*module.program.get() };
+      let program = module.program;
       program.body.len()
     };
+    let top_level_await = self.module_info().top_level_await;
 
-    for i in 0..body_len {
+    for i in 0..top_level_await.unwrap_or(body_len) {
       let module = &self.modules.modules[module_id];
-      let program = unsafe { &*module.program.get() };
+      let program = module.program;
       let node = &program.body[i];
       self.init_statement(node);
     }
 
+    if let Some(start) = top_level_await {
+      self.exec_after_top_level_await(module_id, start);
+    }
+
+    if let Some(commonjs) = self.module_info().commonjs {
+      self.init_commonjs_exports(commonjs);
+    }
+
     self.scoping.call.pop();
 
     let module = self.module_info_mut();
@@ -278,10 +470,89 @@ impl<'a> Analyzer<'a> {
       self.init_import_declaration(node);
     }
 
+    // Writes of this module may affect the modules suspended by top-level `await`,
+    // which must be settled before their dependents read their exports
+    self.call_exhaustive_callbacks();
+
     self.replace_variable_scope(old_variable_scope);
     self.set_current_module(old_module);
   }
 
+  /// Other modules and pending jobs may run while the module is suspended by top-level `await`,
+  /// thus the rest of the module is executed like the body of an async function.
+  /// Dependents are still executed after it, so they observe the post-await state.
+  fn exec_after_top_level_await(&mut self, module_id: ModuleId, start: usize) {
+    let module = &self.modules.modules[module_id];
+    let program = module.program;
+    let variable_scope = module.variable_scope;
+    let callee = module.callee;
+
+    self.exec_async_or_generator_fn(move |analyzer| {
+      // May be re-executed as an exhaustive callback, outside of the module
+      let old_module = analyzer.set_current_module(module_id);
+      let old_variable_scope = analyzer.replace_variable_scope(Some(variable_scope));
+      let cf_scope_depth = analyzer.scoping.cf.current_depth();
+      analyzer.scoping.call.push(CallScope::new_in(
+        AstKind2::ENVIRONMENT,
+        callee,
+        old_module,
+        None,
+        cf_scope_depth,
+        variable_scope,
+        true,
+        false,
+      ));
+
+      for node in &program.body[start..] {
+        analyzer.init_statement(node);
+      }
+
+      analyzer.scoping.call.pop();
+      analyzer.replace_variable_scope(old_variable_scope);
+      analyzer.set_current_module(old_module);
+      analyzer.factory.never
+    });
+  }
+
+  /// Collect `module.exports` and its known properties as the exports of a CommonJS module
+  fn init_commonjs_exports(&mut self, commonjs: CommonJsModule<'a>) {
+    let call_id = self.module_info().call_id;
+    let exports =
+      commonjs.module.get_property(self, self.factory.no_dep, builtin_string!("exports"));
+    let mut named_exports = FxHashMap::default();
+    let keys = exports.get_keys(self, false);
+    if let Some(keys) = &keys {
+      for (_, key) in keys {
+        if let Some(LiteralValue::String(name, _)) = key.get_literal(self) {
+          named_exports.insert(*name, ExportedValue::CommonJs(exports, *name, call_id));
+        }
+      }
+    }
+
+    let module = self.module_info_mut();
+    module.default_export = Some(Some(exports));
+    module.named_exports = named_exports;
+    module.reexport_unknown = keys.is_none();
+  }
+
+  /// The value returned by `require()`
+  pub fn get_module_exports(&mut self, module_id: ModuleId) -> Entity<'a> {
+    let module = &self.modules.modules[module_id];
+    match (module.commonjs, module.default_export) {
+      (Some(_), Some(Some(exports))) => exports,
+      // Circular `require()`
+      (Some(commonjs), _) => {
+        commonjs.module.get_property(self, self.factory.no_dep, builtin_string!("exports"))
+      }
+      (None, _) if module.is_commonjs => self.factory.unknown,
+      // The JSON value itself, instead of a namespace object with `default`
+      (None, default_export) if module.is_json => {
+        default_export.flatten().unwrap_or(self.factory.unknown)
+      }
+      (None, _) => module.module_object,
+    }
+  }
+
   pub fn include_exports(&mut self, module_id: ModuleId) {
     let module = &mut self.modules.modules[module_id];
     if module.exports_included {
@@ -303,50 +574,111 @@ impl<'a> Analyzer<'a> {
     }
   }
 
+  /// Only includes the listed exports, the rest are considered unused
+  pub fn include_exports_by_names(&mut self, module_id: ModuleId, names: &[String]) {
+    let call_id = self.modules.modules[module_id].call_id;
+    self.include(call_id);
+    for name in names {
+      let name = Atom::from(self.allocator.alloc_str(name));
+      match self.get_export_value_by_name(module_id, name, &mut FxHashSet::default()) {
+        Some(value) => self.include(value),
+        None => {
+          let path = self.modules.modules[module_id].path;
+          self.add_diagnostic(
+            DiagnosticCode::MissingExport,
+            format!("Export `{name}` is not found in entry {path}"),
+          );
+        }
+      }
+    }
+  }
+
   fn get_named_export_value(
     &mut self,
     module_id: ModuleId,
     named_export: ExportedValue<'a>,
   ) -> Entity<'a> {
+    let value = self.read_named_export_value(module_id, named_export);
+    self.unwrap_export_value(value)
+  }
+
+  /// `None` if the export is read before initialized
+  fn read_named_export_value(
+    &mut self,
+    module_id: ModuleId,
+    named_export: ExportedValue<'a>,
+  ) -> EntityOrTDZ<'a> {
     match named_export {
       ExportedValue::Variable(scope, symbol, dep) => {
         let old_module = self.set_current_module(module_id);
-        let value = self.read_on_scope(scope, symbol).unwrap();
-        // TODO: handle TDZ
-        let value = value.unwrap_or(self.factory.unknown);
+        let value = self.read_on_scope_may_tdz(scope, symbol).unwrap();
+        if value.is_none() {
+          // Keep the export, otherwise the import fails with a `SyntaxError` instead
+          let decl_node = self.variable(scope, symbol).unwrap().borrow().decl_node;
+          self.include((dep, decl_node));
+        }
         self.set_current_module(old_module);
-        self.factory.computed(value, dep)
+        value.map(|value| self.factory.computed(value, dep))
       }
-      ExportedValue::Function(entity, dep) => self.factory.computed(entity, dep),
-      ExportedValue::Namespace(entity, dep) => self.factory.computed(entity, dep),
+      ExportedValue::Function(entity, dep) => Some(self.factory.computed(entity, dep)),
+      ExportedValue::Namespace(entity, dep) => Some(self.factory.computed(entity, dep)),
       ExportedValue::ReExport(module, name, dep) => {
         let value = self
-          .get_export_value_by_name(module, name, &mut FxHashSet::default())
-          .unwrap_or(self.factory.unknown);
-        self.factory.computed(value, dep)
+          .read_export_by_name(module, name, &mut FxHashSet::default())
+          .unwrap_or(Some(self.factory.unknown));
+        value.map(|value| self.factory.computed(value, dep))
+      }
+      ExportedValue::CommonJs(exports, name, dep) => {
+        let key = self.factory.unmangable_string(&*self.factory.alloc(name));
+        let value = exports.get_property(self, self.factory.no_dep, key);
+        Some(self.factory.computed(value, dep))
       }
-      ExportedValue::Unknown(dep) => self.factory.computed_unknown(dep),
+      ExportedValue::Unknown(dep) => Some(self.factory.computed_unknown(dep)),
     }
   }
 
+  /// Reading an export in TDZ throws a `ReferenceError`
+  pub fn unwrap_export_value(&mut self, value: EntityOrTDZ<'a>) -> Entity<'a> {
+    value.unwrap_or_else(|| {
+      self.handle_tdz();
+      self.factory.unknown
+    })
+  }
+
   pub fn get_export_value_by_name(
     &mut self,
     module_id: ModuleId,
     name: Atom<'a>,
     searched: &mut FxHashSet<ModuleId>,
   ) -> Option<Entity<'a>> {
+    let value = self.read_export_by_name(module_id, name, searched)?;
+    Some(self.unwrap_export_value(value))
+  }
+
+  /// Like `get_export_value_by_name`, but `Some(None)` if the export is read before initialized
+  pub fn read_export_by_name(
+    &mut self,
+    module_id: ModuleId,
+    name: Atom<'a>,
+    searched: &mut FxHashSet<ModuleId>,
+  ) -> Option<EntityOrTDZ<'a>> {
     if !searched.insert(module_id) {
       return None;
     }
     let module = &self.modules.modules[module_id];
     if name == "default" {
-      module.default_export.map(|e| e.unwrap_or(self.factory.unknown))
+      module.default_export
     } else if let Some(exported_value) = module.named_exports.get(&name) {
-      Some(self.get_named_export_value(module_id, *exported_value))
+      Some(self.read_named_export_value(module_id, *exported_value))
+    } else if module.is_commonjs {
+      // Properties of `module.exports` may be unknown before the module is executed
+      let exports = self.get_module_exports(module_id);
+      let key = self.factory.unmangable_string(&*self.factory.alloc(name));
+      Some(Some(exports.get_property(self, self.factory.no_dep, key)))
     } else {
       for reexport_module_id in module.reexport_all.clone() {
-        if let Some(entity) = self.get_export_value_by_name(reexport_module_id, name, searched) {
-          return Some(entity);
+        if let Some(value) = self.read_export_by_name(reexport_module_id, name, searched) {
+          return Some(value);
         }
       }
       None
@@ -373,6 +705,26 @@ impl<'a> Analyzer<'a> {
     false
   }
 
+  /// Returns `false` if the module definitely doesn't export `name`
+  pub fn may_module_export(
+    &self,
+    module_id: ModuleId,
+    name: &str,
+    searched: &mut FxHashSet<ModuleId>,
+  ) -> bool {
+    if !searched.insert(module_id) {
+      return false;
+    }
+    let module = &self.modules.modules[module_id];
+    if module.reexport_unknown || module.named_exports.contains_key(name) {
+      return true;
+    }
+    if name == "default" && module.default_export.is_some() {
+      return true;
+    }
+    module.reexport_all.iter().any(|id| self.may_module_export(*id, name, searched))
+  }
+
   pub fn get_exported_keys(
     &self,
     module_id: ModuleId,
@@ -397,10 +749,9 @@ impl<'a> Analyzer<'a> {
       );
     }
     if searched.is_empty()
-      && let Some(default_export) = &module.default_export
-      && default_export.is_some()
+      && let Some(default_export) = module.default_export
     {
-      keys.push(self.factory.computed(builtin_string!("default"), *default_export));
+      keys.push(self.factory.computed(builtin_string!("default"), default_export));
     }
 
     keys
@@ -8,7 +8,7 @@ use crate::{
   ast::{AstKind2, DeclarationKind},
   entity::Entity,
   transformer::Transformer,
-  utils::CalleeNode,
+  utils::{CalleeNode, is_server_function},
   value::{cache::FnCacheTrackingData, call::FnCallInfo},
 };
 
@@ -17,7 +17,11 @@ impl<'a> Analyzer<'a> {
     &mut self,
     node: &'a ArrowFunctionExpression<'a>,
   ) -> Entity<'a> {
-    self.new_function(CalleeNode::ArrowFunctionExpression(node), false).0.into()
+    let function = self.new_function(CalleeNode::ArrowFunctionExpression(node), false).0.into();
+    if is_server_function(Some(&node.body)) {
+      self.include(function);
+    }
+    function
   }
 
   pub fn call_arrow_function_expression(
@@ -1,15 +1,29 @@
 use oxc::ast::ast::{AwaitExpression, Expression};
 
-use crate::{analyzer::Analyzer, entity::Entity, transformer::Transformer, utils::ast::AstKind2};
+use crate::{
+  analyzer::Analyzer,
+  diagnostic::DiagnosticCode,
+  entity::Entity,
+  transformer::Transformer,
+  utils::{CalleeNode, ast::AstKind2},
+};
 
 impl<'a> Analyzer<'a> {
   pub fn exec_await_expression(&mut self, node: &'a AwaitExpression<'a>) -> Entity<'a> {
     let call_scope = self.call_scope_mut();
+    let is_top_level = matches!(call_scope.callee.node, CalleeNode::Module);
     if !call_scope.is_async {
-      self.add_diagnostic("SyntaxError: await is only valid in async functions");
+      self.add_diagnostic(
+        DiagnosticCode::ParseError,
+        "SyntaxError: await is only valid in async functions",
+      );
     }
 
     self.global_effect();
+    if is_top_level {
+      // Top-level `await` affects the execution order of modules
+      self.include(AstKind2::TopLevelAwait(node));
+    }
 
     let value = self.exec_expression(&node.argument);
     value.r#await(self, AstKind2::AwaitExpression(node))
@@ -29,6 +43,12 @@ impl<'a> Transformer<'a> {
     if has_effect {
       let argument = self.transform_expression(argument, true).unwrap();
       Some(self.ast.expression_await(*span, argument))
+    } else if self.is_included(AstKind2::TopLevelAwait(node)) {
+      // The module is still suspended, even if the awaited value is unused
+      let argument = self
+        .transform_expression(argument, need_val)
+        .unwrap_or_else(|| self.build_unused_expression(*span));
+      Some(self.ast.expression_await(*span, argument))
     } else {
       self.transform_expression(argument, need_val)
     }
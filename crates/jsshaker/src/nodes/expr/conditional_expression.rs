@@ -94,7 +94,7 @@ impl<'a> Transformer<'a> {
               maybe_true,
               maybe_false,
               span,
-              span.source_text(self.semantic.source_text())
+              span.source_text(self.semantic().source_text())
             );
           }
 
@@ -1,8 +1,11 @@
-use oxc::ast::ast::{Expression, ImportExpression};
+use std::path::Path;
+
+use globset::GlobBuilder;
+use oxc::ast::ast::{BinaryOperator, Expression, ImportExpression};
 
 use crate::{
-  analyzer::Analyzer, build_effect, entity::Entity, transformer::Transformer, utils::ast::AstKind2,
-  value::LiteralValue,
+  analyzer::Analyzer, build_effect, entity::Entity, module::ModuleId, transformer::Transformer,
+  utils::ast::AstKind2, value::LiteralValue, vfs::normalize_path,
 };
 
 impl<'a> Analyzer<'a> {
@@ -11,17 +14,138 @@ impl<'a> Analyzer<'a> {
     let options = node.options.as_ref().map(|option| self.exec_expression(option));
     let dep = self.dep((AstKind2::ImportExpression(node), specifier, options));
 
-    if let Some(LiteralValue::String(specifier, _m)) = specifier.get_literal(self)
-      && let Some(module_id) = self.resolve_and_parse_module(specifier)
-    {
-      self.push_non_det_cf_scope();
+    // Whether the specifier may be external or unresolved, whose module is unknown
+    let mut has_unknown = false;
+    let module_ids = if let Some(literals) = specifier.get_literals(self) {
+      let mut module_ids = vec![];
+      for literal in &literals {
+        match literal {
+          LiteralValue::String(specifier, _m) => match self.resolve_and_parse_module(specifier) {
+            Some(module_id) => module_ids.push(module_id),
+            None => has_unknown = true,
+          },
+          _ => has_unknown = true,
+        }
+      }
+      module_ids
+    } else if self.config.glob_dynamic_import {
+      // The runtime value may not match any of the files
+      has_unknown = true;
+      self.resolve_glob_import(&node.source)
+    } else {
+      vec![]
+    };
+    if module_ids.is_empty() {
+      return self.factory.computed_unknown(dep);
+    }
+
+    let mut values = self.factory.vec();
+    self.push_non_det_cf_scope();
+    for module_id in module_ids {
       self.exec_module(module_id);
-      self.pop_cf_scope();
-      return self.factory.computed_unknown((module_id, dep));
+
+      if self.modules.modules[module_id].side_effects {
+        // Like import declarations, the module is always loaded
+        self.include(dep);
+      }
+
+      // The namespace object is a thenable if the module exports `then`
+      values.push(if self.may_module_export(module_id, "then", &mut Default::default()) {
+        self.factory.computed_unknown(module_id)
+      } else {
+        // Settled asynchronously after the module is evaluated, and rejected if loading fails,
+        // so awaiting it is never removed
+        let promise = self.new_promise();
+        promise.add_fulfilled(self, self.modules.modules[module_id].module_object);
+        promise.add_rejected(self, self.factory.computed_unknown(dep));
+        promise.deps.borrow_mut().push(dep);
+        promise.into()
+      });
+    }
+    self.pop_cf_scope();
+    if has_unknown {
+      // Not a bare unknown value, which would absorb the other candidates in the union
+      let promise = self.new_promise();
+      promise.add_unknown(self, dep);
+      values.push(promise.into());
+    }
+
+    self.factory.computed_union(values, dep)
+  }
+
+  /// Expands `./dir/${name}.js` or `"./dir/" + name + ".js"` to the matching modules.
+  /// Like Vite, the dynamic parts only match a single path segment.
+  fn resolve_glob_import(&mut self, node: &'a Expression<'a>) -> Vec<ModuleId> {
+    let mut parts = vec![];
+    if !collect_specifier_parts(node, &mut parts) {
+      return vec![];
     }
+    let Some(Some(prefix)) = parts.first() else {
+      return vec![];
+    };
+    if !prefix.starts_with("./") && !prefix.starts_with("../") {
+      return vec![];
+    }
+
+    let dir_len = prefix.rfind('/').unwrap() + 1;
+    let dir_specifier = &prefix[..dir_len];
+    let mut pattern = globset::escape(&prefix[dir_len..]);
+    for part in &parts[1..] {
+      match part {
+        Some(part) if part.contains('/') => return vec![],
+        Some(part) => pattern.push_str(&globset::escape(part)),
+        None => pattern.push('*'),
+      }
+    }
+    let Ok(glob) = GlobBuilder::new(&pattern).literal_separator(true).build() else {
+      return vec![];
+    };
+    let matcher = glob.compile_matcher();
 
-    self.factory.computed_unknown(dep)
+    let importer = self.module_info().path;
+    let dir = Path::new(importer.as_str()).parent().unwrap_or(Path::new("/"));
+    let dir = normalize_path::normalize(&dir.join(dir_specifier));
+    let mut names = self.vfs.read_dir(&dir.to_string_lossy()).unwrap_or_default();
+    names.sort();
+
+    let mut module_ids = vec![];
+    for name in names {
+      if !matcher.is_match(&name) || dir.join(&name).as_os_str() == importer.as_str() {
+        continue;
+      }
+      if let Some(module_id) = self.resolve_and_parse_module(&format!("{dir_specifier}{name}")) {
+        module_ids.push(module_id);
+      }
+    }
+    module_ids
+  }
+}
+
+/// Static parts are `Some`, dynamic parts are `None`
+fn collect_specifier_parts<'a>(node: &'a Expression<'a>, parts: &mut Vec<Option<&'a str>>) -> bool {
+  match node {
+    Expression::StringLiteral(node) => parts.push(Some(node.value.as_str())),
+    Expression::TemplateLiteral(node) => {
+      for (index, quasi) in node.quasis.iter().enumerate() {
+        if index > 0 {
+          parts.push(None);
+        }
+        let Some(cooked) = &quasi.value.cooked else {
+          return false;
+        };
+        parts.push(Some(cooked.as_str()));
+      }
+    }
+    Expression::BinaryExpression(node) if node.operator == BinaryOperator::Addition => {
+      return collect_specifier_parts(&node.left, parts)
+        && collect_specifier_parts(&node.right, parts);
+    }
+    Expression::ParenthesizedExpression(node) => {
+      return collect_specifier_parts(&node.expression, parts);
+    }
+    _ => parts.push(None),
   }
+  true
 }
 
 impl<'a> Transformer<'a> {
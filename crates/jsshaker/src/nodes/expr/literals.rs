@@ -37,12 +37,16 @@ impl<'a> Transformer<'a> {
     node: &'a StringLiteral,
     need_val: bool,
   ) -> Option<Expression<'a>> {
-    let StringLiteral { span, value, .. } = node;
+    let StringLiteral { span, value, lone_surrogates, .. } = node;
     need_val.then(|| {
-      self.ast.expression_string_literal(
+      let transformed = self.transform_mangable_static_string(AstKind2::StringLiteral(node), value);
+      // The encoded lone surrogates are only kept if the string is not mangled
+      let lone_surrogates = *lone_surrogates && transformed == *value;
+      self.ast.expression_string_literal_with_lone_surrogates(
         *span,
-        self.transform_mangable_static_string(AstKind2::StringLiteral(node), value),
+        transformed,
         None,
+        lone_surrogates,
       )
     })
   }
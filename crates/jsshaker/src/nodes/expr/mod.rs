@@ -78,12 +78,13 @@ impl<'a> Analyzer<'a> {
       Expression::JSXElement(node) => self.exec_jsx_element(node),
       Expression::JSXFragment(node) => self.exec_jsx_fragment(node),
 
-      Expression::V8IntrinsicExpression(_)
-      | Expression::TSAsExpression(_)
-      | Expression::TSInstantiationExpression(_)
-      | Expression::TSTypeAssertion(_)
-      | Expression::TSNonNullExpression(_)
-      | Expression::TSSatisfiesExpression(_) => unreachable!(),
+      Expression::TSAsExpression(node) => self.exec_expression(&node.expression),
+      Expression::TSSatisfiesExpression(node) => self.exec_expression(&node.expression),
+      Expression::TSTypeAssertion(node) => self.exec_expression(&node.expression),
+      Expression::TSNonNullExpression(node) => self.exec_expression(&node.expression),
+      Expression::TSInstantiationExpression(node) => self.exec_expression(&node.expression),
+
+      Expression::V8IntrinsicExpression(_) => unreachable!(),
     };
     self.pop_span();
 
@@ -187,12 +188,20 @@ impl<'a> Transformer<'a> {
       Expression::JSXElement(node) => self.transform_jsx_element(node, need_val),
       Expression::JSXFragment(node) => self.transform_jsx_fragment(node, need_val),
 
-      Expression::V8IntrinsicExpression(_)
-      | Expression::TSAsExpression(_)
-      | Expression::TSInstantiationExpression(_)
-      | Expression::TSTypeAssertion(_)
-      | Expression::TSNonNullExpression(_)
-      | Expression::TSSatisfiesExpression(_) => unreachable!(),
+      // Type information is erased in the output
+      Expression::TSAsExpression(node) => self.transform_expression(&node.expression, need_val),
+      Expression::TSSatisfiesExpression(node) => {
+        self.transform_expression(&node.expression, need_val)
+      }
+      Expression::TSTypeAssertion(node) => self.transform_expression(&node.expression, need_val),
+      Expression::TSNonNullExpression(node) => {
+        self.transform_expression(&node.expression, need_val)
+      }
+      Expression::TSInstantiationExpression(node) => {
+        self.transform_expression(&node.expression, need_val)
+      }
+
+      Expression::V8IntrinsicExpression(_) => unreachable!(),
     };
 
     if let Some(literal) = literal {
@@ -12,7 +12,12 @@ use crate::{
 
 impl<'a> Analyzer<'a> {
   pub fn exec_object_expression(&mut self, node: &'a ObjectExpression) -> Entity<'a> {
-    let object = self.use_mangable_plain_object(AstKind2::ObjectExpression(node));
+    let object = if self.module_info().is_json {
+      // JSON files may also be read by code outside the bundle, thus their keys are kept
+      self.new_empty_object(ObjectPrototype::Builtin(&self.builtins.prototypes.object), None)
+    } else {
+      self.use_mangable_plain_object(AstKind2::ObjectExpression(node))
+    };
 
     for property in &node.properties {
       match property {
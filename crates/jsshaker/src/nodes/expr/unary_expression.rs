@@ -6,8 +6,8 @@ use oxc::{
 use oxc_ecmascript::ToInt32;
 
 use crate::{
-  analyzer::Analyzer, ast::AstKind2, build_effect, entity::Entity, transformer::Transformer,
-  value::LiteralValue,
+  analyzer::Analyzer, ast::AstKind2, build_effect, diagnostic::DiagnosticCode, entity::Entity,
+  transformer::Transformer, value::LiteralValue,
 };
 
 impl<'a> Analyzer<'a> {
@@ -22,7 +22,10 @@ impl<'a> Analyzer<'a> {
           object.delete_property(self, dep, key)
         }
         Expression::PrivateFieldExpression(node) => {
-          self.add_diagnostic("SyntaxError: private fields can't be deleted");
+          self.add_diagnostic(
+            DiagnosticCode::ParseError,
+            "SyntaxError: private fields can't be deleted",
+          );
           let _object = self.exec_expression(&node.object);
           self.include_atom(dep);
         }
@@ -31,8 +34,17 @@ impl<'a> Analyzer<'a> {
           let key = self.exec_expression(&node.expression).coerce_property_key(self);
           object.delete_property(self, dep, key)
         }
-        Expression::Identifier(_node) => {
-          self.add_diagnostic("SyntaxError: Delete of an unqualified identifier in strict mode");
+        Expression::Identifier(node) => {
+          let scoping = self.semantic().scoping();
+          let reference = scoping.get_reference(node.reference_id());
+          let scope = self.semantic().nodes().get_node(reference.node_id()).scope_id();
+          if scoping.scope_flags(scope).is_strict_mode() {
+            self.add_diagnostic(
+              DiagnosticCode::ParseError,
+              "SyntaxError: Delete of an unqualified identifier in strict mode",
+            );
+          }
+          // May delete a property of the global object in sloppy mode
           self.include_atom(dep);
         }
         expr => {
@@ -1,15 +1,30 @@
 use oxc::ast::ast::{Expression, YieldExpression};
 
-use crate::{analyzer::Analyzer, entity::Entity, transformer::Transformer};
+use crate::{analyzer::Analyzer, ast::AstKind2, entity::Entity, transformer::Transformer};
 
 impl<'a> Analyzer<'a> {
   pub fn exec_yield_expression(&mut self, node: &'a YieldExpression<'a>) -> Entity<'a> {
-    self.global_effect();
+    let Some(generator) = self.call_scope().generator else {
+      self.global_effect();
 
-    if let Some(argument) = &node.argument {
-      let argument = self.exec_expression(argument);
-      argument.include(self);
+      if let Some(argument) = &node.argument {
+        let argument = self.exec_expression(argument);
+        self.include((argument, AstKind2::YieldExpression(node)));
+      }
+      return self.factory.unknown;
+    };
+
+    let argument =
+      node.argument.as_ref().map_or(self.factory.undefined, |node| self.exec_expression(node));
+    let dep = self.dep(AstKind2::YieldExpression(node));
+    if node.delegate {
+      let (elements, rest, dep) = argument.iterated(self, dep);
+      generator.add_yielded(self, dep, &elements, rest);
+    } else {
+      generator.add_yielded(self, dep, &[argument], None);
     }
+
+    // The value passed to `next()`, or the value returned by the delegated iterator
     self.factory.unknown
   }
 }
@@ -22,7 +37,9 @@ impl<'a> Transformer<'a> {
   ) -> Option<Expression<'a>> {
     let YieldExpression { span, delegate, argument } = node;
 
-    let argument = argument.as_ref().map(|node| self.transform_expression(node, true).unwrap());
+    let need_argument = *delegate || self.is_included(AstKind2::YieldExpression(node));
+    let argument =
+      argument.as_ref().and_then(|node| self.transform_expression(node, need_argument));
 
     Some(self.ast.expression_yield(*span, *delegate, argument))
   }
@@ -12,8 +12,8 @@ use crate::{
   dep::DepAtom,
   entity::Entity,
   transformer::Transformer,
-  utils::CalleeNode,
-  value::{cache::FnCacheTrackingData, call::FnCallInfo},
+  utils::{CalleeNode, is_server_function},
+  value::{cache::FnCacheTrackingData, call::FnCallInfo, generator::GeneratorValue},
 };
 
 impl<'a> Analyzer<'a> {
@@ -21,7 +21,11 @@ impl<'a> Analyzer<'a> {
     if self.has_no_shake_notation(node.span) {
       return self.factory.computed_unknown(AstKind2::FunctionNoShake(node));
     }
-    self.new_function(CalleeNode::Function(node), true).0.into()
+    let function = self.new_function(CalleeNode::Function(node), true).0.into();
+    if is_server_function(node.body.as_deref()) {
+      self.include(function);
+    }
+    function
   }
 
   pub fn declare_function(
@@ -39,6 +43,10 @@ impl<'a> Analyzer<'a> {
         DeclarationKind::Function,
         Some(self.factory.computed(entity, AstKind2::BindingIdentifier(id))),
       );
+      if is_server_function(node.body.as_deref()) {
+        // The declaration is kept as well
+        self.include(AstKind2::BindingIdentifier(id));
+      }
     }
 
     entity
@@ -49,8 +57,9 @@ impl<'a> Analyzer<'a> {
     node: &'a Function<'a>,
     info: FnCallInfo<'a>,
   ) -> (Entity<'a>, FnCacheTrackingData<'a>) {
-    let runner = move |analyzer: &mut Analyzer<'a>| {
+    let runner = move |analyzer: &mut Analyzer<'a>, generator: Option<&'a GeneratorValue<'a>>| {
       analyzer.push_call_scope(info, node.r#async, node.generator);
+      analyzer.call_scope_mut().generator = generator;
 
       let factory = analyzer.factory;
       let variable_scope = analyzer.variable_scope_mut();
@@ -81,15 +90,19 @@ impl<'a> Analyzer<'a> {
       analyzer.pop_call_scope()
     };
 
-    if !info.include && (node.r#async || node.generator) {
+    if !info.include && node.generator && !node.r#async {
+      let generator =
+        self.exec_generator_body(move |analyzer, generator| runner(analyzer, Some(generator)).0);
+      (generator.into(), FnCacheTrackingData::worst_case())
+    } else if !info.include && (node.r#async || node.generator) {
       // Too complex to analyze the control flow, thus run exhaustively
       self.exec_async_or_generator_fn(move |analyzer| {
-        runner(analyzer).0.include(analyzer);
+        runner(analyzer, None).0.include(analyzer);
         analyzer.factory.never
       });
       (self.factory.unknown, FnCacheTrackingData::worst_case())
     } else {
-      runner(self)
+      runner(self, None)
     }
   }
 }
@@ -3,7 +3,10 @@ use oxc::{
   ast::ast::{IdentifierReference, PropertyKey},
 };
 
-use crate::{analyzer::Analyzer, ast::AstKind2, entity::Entity, transformer::Transformer};
+use crate::{
+  analyzer::Analyzer, ast::AstKind2, diagnostic::DiagnosticCode, entity::Entity,
+  transformer::Transformer,
+};
 
 impl<'a> Analyzer<'a> {
   pub fn exec_identifier_reference_read(
@@ -15,7 +18,15 @@ impl<'a> Analyzer<'a> {
 
     let dep = AstKind2::IdentifierReference(node);
 
-    if let Some(symbol) = symbol {
+    if self.is_in_with_scope(node) {
+      // May be a property of the `with` object, which may have getters
+      if let Some(symbol) = symbol {
+        self.include_symbol(symbol);
+      }
+      self.include(dep);
+      self.global_effect();
+      self.factory.computed_unknown(dep)
+    } else if let Some(symbol) = symbol {
       // Known symbol
       if let Some(value) = self.read_symbol(symbol) {
         value
@@ -29,6 +40,16 @@ impl<'a> Analyzer<'a> {
       let arguments_included = self.include_arguments();
       self.call_scope_mut().need_include_arguments = !arguments_included;
       self.factory.unknown
+    } else if let Some(commonjs) = self.module_info().commonjs
+      && let Some(value) = match node.name.as_str() {
+        "module" => Some(commonjs.module),
+        "exports" => Some(commonjs.exports),
+        "require" => Some(commonjs.require),
+        _ => None,
+      }
+    {
+      // CommonJS module scope
+      value
     } else if let Some(global) = self.builtins.globals.get(node.name.as_str()) {
       // Known global
       *global
@@ -54,10 +75,19 @@ impl<'a> Analyzer<'a> {
     assert!(reference.is_write());
     let symbol = reference.symbol_id();
 
-    if let Some(symbol) = symbol {
+    if self.is_in_with_scope(node) {
+      // May be a property of the `with` object, which may have setters
+      if let Some(symbol) = symbol {
+        self.include_symbol(symbol);
+      }
+      self.include(dep);
+      self.include(value);
+      self.global_effect();
+    } else if let Some(symbol) = symbol {
       self.write_symbol(symbol, value);
     } else if self.builtins.globals.contains_key(node.name.as_str()) {
       self.add_diagnostic(
+        DiagnosticCode::BuiltinMutation,
         "Should not write to builtin object, it may cause unexpected tree-shaking behavior",
       );
     } else {
@@ -99,7 +129,7 @@ impl<'a> Transformer<'a> {
     if need_val || self.is_included(AstKind2::IdentifierReference(node)) {
       let IdentifierReference { span, name, .. } = node;
 
-      let reference = self.semantic.scoping().get_reference(node.reference_id());
+      let reference = self.semantic().scoping().get_reference(node.reference_id());
       if let Some(symbol) = reference.symbol_id() {
         self.update_var_decl_state(symbol, false);
       }
@@ -16,10 +16,11 @@ impl<'a> Analyzer<'a> {
       right
     };
 
-    let (elements, mut rest, dep, array_ids) = right.iterate(self, AstKind2::ForOfStatement(node));
+    let (elements, mut rest, dep, live_iterables) =
+      right.iterate(self, AstKind2::ForOfStatement(node));
 
     let original_versions =
-      array_ids.iter().map(|array_id| array_id.as_ref().version.get()).collect::<Vec<_>>();
+      live_iterables.iter().map(|iterable| iterable.version()).collect::<Vec<_>>();
 
     self.push_cf_scope_with_deps(CfScopeKind::LoopBreak, self.factory.vec1(dep), false);
     for element in elements {
@@ -38,10 +39,9 @@ impl<'a> Analyzer<'a> {
       self.pop_variable_scope();
       self.pop_cf_scope();
 
-      for (array_id, original_version) in array_ids.iter().zip(&original_versions) {
-        let array = array_id.as_ref();
-        if array.version.get() != *original_version {
-          self.include(array);
+      for (iterable, original_version) in live_iterables.iter().zip(&original_versions) {
+        if iterable.version() != *original_version {
+          self.include(iterable.as_value());
           rest = Some(self.factory.unknown);
           break;
         }
@@ -15,6 +15,7 @@ mod switch_statement;
 mod throw_statement;
 mod try_statement;
 mod while_statement;
+mod with_statement;
 
 use oxc::{
   ast::{
@@ -74,7 +75,7 @@ impl<'a> Analyzer<'a> {
       Statement::ThrowStatement(node) => self.exec_throw_statement(node),
       Statement::EmptyStatement(_) => {}
       Statement::DebuggerStatement(_node) => {}
-      Statement::WithStatement(_node) => unimplemented!("with statement"),
+      Statement::WithStatement(node) => self.exec_with_statement(node),
     }
     self.pop_span();
   }
@@ -119,7 +120,7 @@ impl<'a> Transformer<'a> {
       Statement::ThrowStatement(node) => self.transform_throw_statement(node),
       Statement::EmptyStatement(_) => None,
       Statement::DebuggerStatement(node) => Some(self.ast.statement_debugger(node.span())),
-      Statement::WithStatement(_node) => unreachable!(),
+      Statement::WithStatement(node) => self.transform_with_statement(node),
     }
   }
 }
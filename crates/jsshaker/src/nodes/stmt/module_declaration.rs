@@ -1,7 +1,11 @@
-use oxc::ast::ast::{
-  ExportDefaultDeclaration, ExportDefaultDeclarationKind, ExportNamedDeclaration,
-  ImportDeclaration, ImportDeclarationSpecifier, ImportDefaultSpecifier, ImportNamespaceSpecifier,
-  ImportOrExportKind, ImportSpecifier, ModuleDeclaration, ModuleExportName, Statement,
+use oxc::{
+  ast::ast::{
+    ExportDefaultDeclaration, ExportDefaultDeclarationKind, ExportNamedDeclaration,
+    ImportDeclaration, ImportDeclarationSpecifier, ImportDefaultSpecifier,
+    ImportNamespaceSpecifier, ImportOrExportKind, ImportSpecifier, ModuleDeclaration,
+    ModuleExportName, Statement,
+  },
+  span::Atom,
 };
 
 use crate::{
@@ -56,14 +60,23 @@ impl<'a> Analyzer<'a> {
               // Pass `exporting` as `None` because it is actually used as an expression
               self.declare_class(node, None);
             }
+            self.module_info_mut().default_export = Some(None);
+          }
+          _ => {
+            // In TDZ until the expression is evaluated
+            self.module_info_mut().default_export = Some(None);
           }
-          _ => {}
         }
       }
       ModuleDeclaration::ExportNamedDeclaration(node) => {
         if let Some(source) = &node.source {
           // export { ... } from 'module'
-          if let Some(module_id) = module.resolved_imports.get(&source.value) {
+          let resolved = module.resolved_imports.get(&source.value).copied();
+          if resolved.is_none_or(|module_id| self.modules.modules[module_id].side_effects) {
+            self.include(AstKind2::ExportNamedDeclaration(node));
+          }
+          let module = self.module_info_mut();
+          if let Some(module_id) = &resolved {
             for specifier in &node.specifiers {
               let exported = specifier.exported.name();
               let local = specifier.local.name();
@@ -124,9 +137,16 @@ impl<'a> Analyzer<'a> {
       None
     };
 
+    if resolved.is_none_or(|module_id| self.modules.modules[module_id].side_effects) {
+      // Otherwise the import is only kept when some of its bindings are used
+      self.include(AstKind2::ImportDeclaration(node));
+    }
+
+    let mut circular = false;
     if let Some(resolved) = resolved {
       if self.modules.modules[resolved].initializing {
-        // Circular dependency
+        // Circular dependency, the bindings are initialized after the module is initialized
+        circular = true;
         let module = self.current_module;
         let scope = self.scoping.variable.top().unwrap();
         self.modules.modules[resolved].circular_imports.push((module, scope, node));
@@ -136,6 +156,22 @@ impl<'a> Analyzer<'a> {
 
     if let Some(specifiers) = &node.specifiers {
       for specifier in specifiers {
+        if circular && let Some(resolved) = resolved {
+          let name = match specifier {
+            ImportDeclarationSpecifier::ImportDefaultSpecifier(_node) => {
+              Some(Atom::from("default"))
+            }
+            ImportDeclarationSpecifier::ImportNamespaceSpecifier(_node) => None,
+            ImportDeclarationSpecifier::ImportSpecifier(node) => Some(node.imported.name()),
+          };
+          if let Some(name) = name {
+            // Until then, reading the binding reads the export, which may be in TDZ
+            let scope = self.scoping.variable.top().unwrap();
+            let symbol = specifier.local().symbol_id();
+            self.modules.live_imports.insert((scope, symbol), (resolved, name));
+            continue;
+          }
+        }
         let value = if let Some(known) = known {
           match specifier {
             ImportDeclarationSpecifier::ImportDefaultSpecifier(_node) => known.default,
@@ -153,7 +189,8 @@ impl<'a> Analyzer<'a> {
           let module = &self.modules.modules[resolved];
           match specifier {
             ImportDeclarationSpecifier::ImportDefaultSpecifier(_node) => {
-              module.default_export.flatten().unwrap_or(self.factory.unknown)
+              let value = module.default_export.unwrap_or(Some(self.factory.unknown));
+              self.unwrap_export_value(value)
             }
             ImportDeclarationSpecifier::ImportNamespaceSpecifier(_node) => module.module_object,
             ImportDeclarationSpecifier::ImportSpecifier(node) => self
@@ -211,6 +248,7 @@ impl<'a> Transformer<'a> {
       ModuleDeclaration::ImportDeclaration(node) => {
         let ImportDeclaration { span, specifiers, source, with_clause, import_kind, phase } =
           node.as_ref();
+        let need_import = self.is_included(AstKind2::ImportDeclaration(node));
         if let Some(specifiers) = specifiers {
           let mut transformed_specifiers = self.ast.vec();
           for specifier in specifiers {
@@ -243,6 +281,9 @@ impl<'a> Transformer<'a> {
               transformed_specifiers.push(specifier);
             }
           }
+          if transformed_specifiers.is_empty() && !need_import {
+            return None;
+          }
           Some(
             self
               .ast
@@ -257,7 +298,7 @@ impl<'a> Transformer<'a> {
               .into(),
           )
         } else {
-          Some(
+          need_import.then(|| {
             self
               .ast
               .module_declaration_import_declaration(
@@ -268,8 +309,8 @@ impl<'a> Transformer<'a> {
                 self.clone_node(with_clause),
                 *import_kind,
               )
-              .into(),
-          )
+              .into()
+          })
         }
       }
       ModuleDeclaration::ExportNamedDeclaration(node) => {
@@ -309,7 +350,9 @@ impl<'a> Transformer<'a> {
             }
           }
           if transformed_specifiers.is_empty() {
-            source.as_ref().map(|source| {
+            // Side-effect-free modules are not included
+            let need_import = self.is_included(AstKind2::ExportNamedDeclaration(node));
+            source.as_ref().filter(|_| need_import).map(|source| {
               self
                 .ast
                 .module_declaration_import_declaration(
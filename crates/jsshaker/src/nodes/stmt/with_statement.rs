@@ -0,0 +1,66 @@
+use oxc::{
+  ast::ast::{IdentifierReference, Statement, WithStatement},
+  semantic::ScopeFlags,
+};
+
+use crate::{analyzer::Analyzer, ast::AstKind2, transformer::Transformer};
+
+impl<'a> Analyzer<'a> {
+  /// Identifiers in the body are resolved dynamically, see `is_in_with_scope`
+  pub fn exec_with_statement(&mut self, node: &'a WithStatement<'a>) {
+    let object = self.exec_expression(&node.object);
+    let is_nullish = object.test_nullish();
+    if is_nullish != Some(false) {
+      if is_nullish == Some(true) {
+        self.throw_builtin_error("Cannot convert undefined or null to object");
+      }
+      if self.config.preserve_exceptions {
+        self.include(AstKind2::WithStatement(node));
+      }
+    }
+    // Any property of the object may be read or written via identifiers
+    self.include(object);
+
+    self.exec_statement(&node.body);
+  }
+
+  /// Whether the identifier may resolve to a property of a `with` object,
+  /// i.e. there is a `with` scope between the reference and its declaration.
+  pub fn is_in_with_scope(&self, node: &'a IdentifierReference<'a>) -> bool {
+    if !self.module_info().has_with_statement {
+      return false;
+    }
+    let semantic = self.semantic();
+    let scoping = semantic.scoping();
+    let reference = scoping.get_reference(node.reference_id());
+    let decl_scope = reference.symbol_id().map(|symbol| scoping.symbol_scope_id(symbol));
+    let scope = semantic.nodes().get_node(reference.node_id()).scope_id();
+    for scope in scoping.scope_ancestors(scope) {
+      if Some(scope) == decl_scope {
+        return false;
+      }
+      if scoping.scope_flags(scope).contains(ScopeFlags::With) {
+        return true;
+      }
+    }
+    false
+  }
+}
+
+impl<'a> Transformer<'a> {
+  pub fn transform_with_statement(&self, node: &'a WithStatement<'a>) -> Option<Statement<'a>> {
+    let WithStatement { span, object, body, .. } = node;
+
+    let body = self.transform_statement(body);
+
+    if body.is_some() || self.is_included(AstKind2::WithStatement(node)) {
+      let object = self.transform_expression(object, true).unwrap();
+      let body = body.unwrap_or_else(|| self.ast.statement_empty(*span));
+      Some(self.ast.statement_with(*span, object, body))
+    } else {
+      self
+        .transform_expression(object, false)
+        .map(|expr| self.ast.statement_expression(*span, expr))
+    }
+  }
+}
@@ -0,0 +1,327 @@
+use std::{
+  cell::RefCell,
+  hash::{DefaultHasher, Hash, Hasher},
+  mem,
+  ptr::NonNull,
+  rc::Rc,
+};
+
+use line_index::LineIndex;
+use oxc::{
+  allocator::Allocator,
+  ast::ast::Program,
+  diagnostics::OxcDiagnostic,
+  parser::Parser,
+  semantic::{Semantic, SemanticBuilder},
+  span::{SourceType, Span},
+  syntax::module_record::ModuleRecord,
+};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{analyzer::Analyzer, json::collect_json_imports, typescript::strip_typescript};
+
+/// An allocator of a module parsed in parallel, which stays at the same address until dropped.
+///
+/// The arena vectors in the AST keep references to the allocator, thus it must not move.
+/// Unlike `Box<Allocator>`, moving the owner doesn't assert unique access to the allocator,
+/// which would invalidate these references.
+struct ModuleAllocator(NonNull<Allocator>);
+
+impl ModuleAllocator {
+  fn new() -> Self {
+    Self(NonNull::from(Box::leak(Box::default())))
+  }
+
+  /// # Safety
+  ///
+  /// The returned reference must not be used after `self` is dropped.
+  unsafe fn get<'a>(&self) -> &'a Allocator {
+    unsafe { self.0.as_ref() }
+  }
+}
+
+impl Drop for ModuleAllocator {
+  fn drop(&mut self) {
+    drop(unsafe { Box::from_raw(self.0.as_ptr()) });
+  }
+}
+
+// SAFETY: It uniquely owns the allocator, which is `Send`.
+unsafe impl Send for ModuleAllocator {}
+
+/// Owns the allocators of the modules parsed in parallel, which must outlive the analyzer
+#[derive(Default)]
+pub struct AllocatorPool(RefCell<Vec<ModuleAllocator>>);
+
+impl AllocatorPool {
+  fn push(&self, allocator: ModuleAllocator) {
+    self.0.borrow_mut().push(allocator);
+  }
+}
+
+/// A module which is parsed and semantically analyzed, but not declared yet.
+///
+/// It is shared with the cache between runs, thus the program is only handed out as a shared
+/// reference, except by `program_mut`.
+pub struct ParsedModule<'a> {
+  pub source_type: SourceType,
+  pub line_index: LineIndex,
+  /// Derived from the unique reference returned by the allocator
+  program: NonNull<Program<'a>>,
+  pub module_record: ModuleRecord<'a>,
+  pub errors: Vec<OxcDiagnostic>,
+  /// Errors of TypeScript syntax that can't be erased
+  pub typescript_errors: Vec<(Span, String)>,
+  /// The TypeScript syntax can't be erased without changing the behavior,
+  /// so the module is left untransformed
+  pub typescript_unsupported: bool,
+  pub semantic: Semantic<'a>,
+}
+
+impl<'a> ParsedModule<'a> {
+  pub fn program(&self) -> &'a Program<'a> {
+    // SAFETY: The program is allocated in an allocator which lives for `'a`, and it is only
+    // mutated via `program_mut`, by the only owner of the module.
+    unsafe { self.program.as_ref() }
+  }
+
+  /// Used for mangling in place, when the module is not cached.
+  ///
+  /// # Safety
+  ///
+  /// The references returned by `program` must not be dereferenced during the mutation. They can
+  /// still be used as the addresses of the nodes, which are the keys of the analysis data.
+  pub unsafe fn program_mut(&mut self) -> &'a mut Program<'a> {
+    unsafe { self.program.as_mut() }
+  }
+}
+
+pub fn parse_module_source<'a>(
+  allocator: &'a Allocator,
+  source_text: &'a str,
+  source_type: SourceType,
+) -> ParsedModule<'a> {
+  let line_index = LineIndex::new(source_text);
+  let parsed = Parser::new(allocator, source_text, source_type).parse();
+  let program = allocator.alloc(parsed.program);
+  let (typescript_errors, typescript_unsupported) = if source_type.is_typescript() {
+    match strip_typescript(allocator, program) {
+      Ok(errors) => (errors, false),
+      Err(errors) => (errors, true),
+    }
+  } else {
+    (vec![], false)
+  };
+  let program = NonNull::from(program);
+  // SAFETY: The program is allocated for `'a`, and no longer mutated since it is borrowed here
+  let semantic = SemanticBuilder::new().build(unsafe { program.as_ref() }).semantic;
+  ParsedModule {
+    source_type,
+    line_index,
+    program,
+    module_record: parsed.module_record,
+    errors: parsed.errors,
+    typescript_errors,
+    typescript_unsupported,
+    semantic,
+  }
+}
+
+struct CachedModule {
+  hash: u64,
+  /// The module is parsed from a JSON file
+  is_json: bool,
+  module: Rc<ParsedModule<'static>>,
+  /// Declared after the module, so it is dropped after the module which references it
+  _allocator: ModuleAllocator,
+}
+
+/// Parsed modules kept between runs of a session, keyed by path
+#[derive(Default)]
+pub struct ModuleCache {
+  modules: FxHashMap<String, CachedModule>,
+  /// Modules which may have changed since the last run, whose content hashes are checked
+  changed: FxHashSet<String>,
+  /// Modules used by the current run, the others are dropped when it finishes
+  used: FxHashSet<String>,
+}
+
+impl ModuleCache {
+  pub fn mark_changed(&mut self, path: String) {
+    self.changed.insert(path);
+  }
+
+  /// Returns the cached module if it is not changed. Without `hash`, the module is only checked
+  /// if it is marked as changed, so that it doesn't need to be read.
+  ///
+  /// The cached modules are stored with the placeholder lifetime `'static`, and handed out for
+  /// the lifetime `'a` of a run. It is sound because:
+  /// - A module only references its own allocator, which is stored along with it.
+  /// - The cache outlives the runs, and it is not accessed by anything else during a run.
+  /// - A module handed out in a run is not replaced in the same run, because each path is parsed
+  ///   once per run, and a preparsed module not used for it is dropped before. `finish` only
+  ///   drops the modules not used by the run.
+  /// - The cached modules are never mutated, see `ParsedModule::program_mut`.
+  pub fn get<'a>(
+    &mut self,
+    path: &str,
+    hash: Option<u64>,
+    is_json: bool,
+  ) -> Option<Rc<ParsedModule<'a>>> {
+    let cached = self.modules.get(path)?;
+    if cached.is_json != is_json
+      || hash.map_or(self.changed.contains(path), |hash| hash != cached.hash)
+    {
+      return None;
+    }
+    self.used.insert(path.to_string());
+    Some(unsafe {
+      mem::transmute::<Rc<ParsedModule<'static>>, Rc<ParsedModule<'a>>>(cached.module.clone())
+    })
+  }
+
+  /// See `get` for the lifetime of the module
+  fn insert<'a>(
+    &mut self,
+    path: String,
+    hash: u64,
+    is_json: bool,
+    allocator: ModuleAllocator,
+    module: Rc<ParsedModule<'a>>,
+  ) {
+    let module =
+      unsafe { mem::transmute::<Rc<ParsedModule<'a>>, Rc<ParsedModule<'static>>>(module) };
+    self.used.insert(path.clone());
+    self.modules.insert(path, CachedModule { hash, is_json, module, _allocator: allocator });
+  }
+
+  /// Parses a module on the analyzer thread into its own allocator, and adds it to the cache
+  pub fn parse_and_insert<'a>(
+    &mut self,
+    path: String,
+    hash: u64,
+    is_json: bool,
+    source_text: &str,
+    source_type: SourceType,
+  ) -> Rc<ParsedModule<'a>> {
+    let allocator = ModuleAllocator::new();
+    // SAFETY: The allocator is moved into the cache along with the module, see `get`
+    let allocator_ref: &'a Allocator = unsafe { allocator.get() };
+    let source_text = allocator_ref.alloc_str(source_text);
+    let module = Rc::new(parse_module_source(allocator_ref, source_text, source_type));
+    self.insert(path, hash, is_json, allocator, module.clone());
+    module
+  }
+
+  /// Drops the modules which are not used by the run, i.e. no longer in the module graph
+  pub fn finish(&mut self) {
+    let used = mem::take(&mut self.used);
+    self.modules.retain(|path, _| used.contains(path));
+    self.changed.clear();
+  }
+}
+
+pub fn hash_source(source_text: &str) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  source_text.hash(&mut hasher);
+  hasher.finish()
+}
+
+/// A module parsed on a worker thread, sent back to the analyzer thread along with its allocator.
+struct ParsedInAllocator<'a> {
+  path: String,
+  hash: u64,
+  module: ParsedModule<'a>,
+  /// Declared after the module, so it is dropped after the module which references it
+  allocator: ModuleAllocator,
+}
+
+// SAFETY: The module is `!Send` only because of the references to its own allocator:
+// - The AST, referenced by `program`, and `ModuleRecord` are allocated in it, and their arena
+//   vectors reference it.
+// - `Semantic` references the AST nodes, whose `Cell`s of semantic IDs are not `Sync`.
+//   Its `Scoping` owns a separate allocator and is `Send` itself.
+// - The other fields are owned data.
+// The allocator and the source text in it are created by the worker for this module only, and
+// the worker keeps no reference after returning. Thus nothing is shared between threads, and all
+// of them are moved to the analyzer thread as a whole, like a single owned value. The module is
+// only shared, via `Rc`, after it is received by the analyzer thread.
+unsafe impl Send for ParsedInAllocator<'_> {}
+
+impl<'a> Analyzer<'a> {
+  /// Discovers the static module graph from the entries, and parses the modules on a thread pool.
+  /// The results are taken by `parse_module`, so the analysis itself stays single-threaded.
+  ///
+  /// Modules which can't be resolved or read are skipped here, and reported when parsed.
+  ///
+  /// With a cache, unchanged modules are reused, and the newly parsed ones are added to it.
+  pub fn preparse_modules(&mut self, entries: Vec<String>, allocators: &'a AllocatorPool) {
+    let mut cache = self.modules.cache.take();
+    let mut visited = FxHashSet::default();
+    let mut pending = entries;
+    while !pending.is_empty() {
+      // The Vfs is not required to be thread-safe, thus files are read serially
+      let mut modules = vec![];
+      let mut sources = vec![];
+      for path in pending.drain(..) {
+        if path.ends_with(".json") || !visited.insert(path.clone()) {
+          continue;
+        }
+        if let Some(module) = cache.as_mut().and_then(|cache| cache.get(&path, None, false)) {
+          modules.push((path, module));
+          continue;
+        }
+        let Ok(source_text) = self.vfs.read_file(&path) else {
+          continue;
+        };
+        let hash = hash_source(&source_text);
+        if let Some(module) = cache.as_mut().and_then(|cache| cache.get(&path, Some(hash), false)) {
+          modules.push((path, module));
+          continue;
+        }
+        let source_type =
+          self.vfs.source_type(&path).unwrap_or_else(|| self.config.default_source_type());
+        sources.push((path, hash, source_text, source_type));
+      }
+
+      let parsed = sources
+        .into_par_iter()
+        .map(|(path, hash, source_text, source_type)| {
+          let allocator = ModuleAllocator::new();
+          // SAFETY: The allocator is moved into the pool, or the cache when it is provided,
+          // before the module is used. Both outlive the analysis, which lives for `'a`.
+          let allocator_ref: &'a Allocator = unsafe { allocator.get() };
+          let source_text = allocator_ref.alloc_str(&source_text);
+          let module = parse_module_source(allocator_ref, source_text, source_type);
+          ParsedInAllocator { path, hash, module, allocator }
+        })
+        .collect::<Vec<_>>();
+
+      for ParsedInAllocator { path, hash, module, allocator } in parsed {
+        let module = Rc::new(module);
+        if let Some(cache) = &mut cache {
+          cache.insert(path.clone(), hash, false, allocator, module.clone());
+        } else {
+          allocators.push(allocator);
+        }
+        modules.push((path, module));
+      }
+
+      for (path, module) in modules {
+        let json_imports = collect_json_imports(module.program());
+        for specifier in module.module_record.requested_modules.keys() {
+          if json_imports.contains(&specifier.as_str()) {
+            continue;
+          }
+          if let Ok(Some(resolved)) = self.vfs.resolve_module(&path, specifier) {
+            pending.push(resolved);
+          }
+        }
+        self.modules.preparsed.insert(path, module);
+      }
+    }
+
+    self.modules.cache = cache;
+  }
+}
@@ -9,6 +9,7 @@ use crate::{
   entity::Entity,
   module::ModuleId,
   utils::{CalleeInfo, ast::AstKind2},
+  value::generator::GeneratorValue,
 };
 
 pub struct CallScope<'a> {
@@ -21,6 +22,8 @@ pub struct CallScope<'a> {
   pub returned_values: Vec<Entity<'a>>,
   pub is_async: bool,
   pub is_generator: bool,
+  /// The generator object resumed by the body, `None` if the generator is not tracked
+  pub generator: Option<&'a GeneratorValue<'a>>,
   pub need_include_arguments: bool,
 
   #[cfg(feature = "flame")]
@@ -48,6 +51,7 @@ impl<'a> CallScope<'a> {
       returned_values: Vec::new(),
       is_async,
       is_generator,
+      generator: None,
       need_include_arguments: false,
 
       #[cfg(feature = "flame")]
@@ -67,7 +67,7 @@ impl<'a> VariableScope<'a> {
 }
 
 impl<'a> Analyzer<'a> {
-  fn variable(
+  pub fn variable(
     &self,
     scope: VariableScopeId,
     symbol: SymbolId,
@@ -158,6 +158,13 @@ impl<'a> Analyzer<'a> {
     } else if let Some(deps) = variable.exhausted {
       drop(variable);
       deps.push(self, self.dep((init_node, value)));
+    } else if let Some(old_value) = variable.value {
+      // Initialized again when the module body after top-level `await` is re-executed
+      variable.exhausted =
+        Some(self.factory.lazy_dep(self.factory.vec1(self.dep((init_node, value, old_value)))));
+      variable.value = Some(self.factory.unknown);
+      drop(variable);
+      self.request_exhaustive_callbacks(ReadWriteTarget::Variable(scope, symbol));
     } else {
       variable.value =
         Some(self.factory.computed(value.unwrap_or(self.factory.undefined), init_node));
@@ -173,10 +180,40 @@ impl<'a> Analyzer<'a> {
     &mut self,
     scope: VariableScopeId,
     symbol: SymbolId,
+  ) -> Option<EntityOrTDZ<'a>> {
+    let value = self.read_on_scope_may_tdz(scope, symbol)?;
+
+    if value.is_none() {
+      // TDZ
+      let decl_node = self.variable(scope, symbol).unwrap().borrow().decl_node;
+      self.include(decl_node);
+      self.handle_tdz();
+    }
+
+    Some(value)
+  }
+
+  /// Like `read_on_scope`, but reading in TDZ is not handled
+  pub fn read_on_scope_may_tdz(
+    &mut self,
+    scope: VariableScopeId,
+    symbol: SymbolId,
   ) -> Option<EntityOrTDZ<'a>> {
     let variable = self.variable(scope, symbol)?.borrow();
     let decl_node = variable.decl_node;
 
+    if variable.value.is_none()
+      && variable.exhausted.is_none()
+      && let Some(&(module_id, name)) = self.modules.live_imports.get(&(scope, symbol))
+    {
+      // Imported from a module which is not initialized yet, see `init_import_declaration`
+      drop(variable);
+      let value = self.read_export_by_name(module_id, name, &mut Default::default());
+      return Some(
+        value.unwrap_or(Some(self.factory.unknown)).map(|v| self.factory.computed(v, decl_node)),
+      );
+    }
+
     let value = variable.value.or_else(|| {
       variable.kind.is_var().then(|| self.factory.computed(self.factory.undefined, decl_node))
     });
@@ -217,12 +254,6 @@ impl<'a> Analyzer<'a> {
       }
     };
 
-    if value.is_none() {
-      // TDZ
-      self.include(decl_node);
-      self.handle_tdz();
-    }
-
     Some(value)
   }
 
@@ -441,6 +472,17 @@ impl<'a> Analyzer<'a> {
     self.mark_unresolved_reference(symbol);
   }
 
+  /// The variable escapes, its value is unknown from now on
+  pub fn include_symbol(&mut self, symbol: SymbolId) {
+    let mut scope = self.scoping.variable.top();
+    while let Some(s) = scope {
+      if self.include_on_scope(s, symbol) {
+        return;
+      }
+      scope = self.scoping.variable.get_parent(s);
+    }
+  }
+
   fn mark_unresolved_reference(&mut self, symbol: SymbolId) {
     if self.semantic().scoping().symbol_flags(symbol).is_function_scoped_declaration() {
       self.mark_untracked_on_scope(symbol);
@@ -0,0 +1,77 @@
+use std::rc::Rc;
+
+use oxc::{codegen::CodegenOptions, minifier::MinifierOptions};
+
+use crate::{
+  JsShakerEntry, JsShakerOptions, JsShakerReturn, TreeShakeConfig, preparse::ModuleCache,
+  tree_shake_with_cache, vfs::Vfs, vfs::normalize_path,
+};
+
+/// Keeps the parsed modules between runs, for watch mode or dev previews.
+///
+/// A run only re-reads and re-parses the modules reported as changed, whose content hashes differ
+/// from the cached ones. This includes the modules loaded lazily, e.g. via `require`, dynamic
+/// `import()` or JSON imports.
+///
+/// Only parsing is cached. The analysis depends on the whole module graph, thus always re-runs.
+/// Reusing the declaration results and the function cache summaries is open work: they are values
+/// allocated for a single run, and would need to be invalidated along the module graph.
+pub struct JsShakerSession<F: Vfs + 'static> {
+  vfs: Rc<F>,
+  entries: Vec<JsShakerEntry>,
+  config: TreeShakeConfig,
+  minify_options: Option<MinifierOptions>,
+  codegen_options: CodegenOptions,
+  source_map: bool,
+  cache: ModuleCache,
+}
+
+impl<F: Vfs + 'static> JsShakerSession<F> {
+  pub fn new(options: JsShakerOptions<F>) -> Self {
+    let JsShakerOptions { vfs, entries, config, minify_options, codegen_options, source_map } =
+      options;
+    Self {
+      vfs: Rc::new(vfs),
+      entries,
+      config,
+      minify_options,
+      codegen_options,
+      source_map,
+      cache: ModuleCache::default(),
+    }
+  }
+
+  pub fn vfs(&self) -> &F {
+    &self.vfs
+  }
+
+  /// Used to apply the changes before calling `update`
+  pub fn vfs_mut(&mut self) -> &mut F {
+    Rc::get_mut(&mut self.vfs).expect("The Vfs is only shared during a run")
+  }
+
+  /// Runs with the cached modules, the first run is a cold run
+  pub fn run(&mut self) -> JsShakerReturn {
+    let result = tree_shake_with_cache(
+      JsShakerOptions {
+        vfs: self.vfs.clone(),
+        entries: self.entries.clone(),
+        config: self.config.clone(),
+        minify_options: self.minify_options.clone(),
+        codegen_options: self.codegen_options.clone(),
+        source_map: self.source_map,
+      },
+      Some(&mut self.cache),
+    );
+    self.cache.finish();
+    result
+  }
+
+  /// Re-runs after the files are changed. Added or removed modules are discovered from the imports.
+  pub fn update(&mut self, changed_paths: &[String]) -> JsShakerReturn {
+    for path in changed_paths {
+      self.cache.mark_changed(normalize_path::normalize_str(path));
+    }
+    self.run()
+  }
+}
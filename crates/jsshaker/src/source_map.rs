@@ -0,0 +1,36 @@
+use oxc_sourcemap::{SourceMap, Token};
+
+/// Composes `map` (output -> input) with `input_map` (input -> original), producing a map from
+/// the output to the original sources. Tokens which have no original position are dropped.
+pub fn compose_source_maps(map: &SourceMap, input_map: &SourceMap) -> SourceMap {
+  let lookup_table = input_map.generate_lookup_table();
+  let tokens = map
+    .get_tokens()
+    .filter_map(|token| {
+      let original =
+        input_map.lookup_token(&lookup_table, token.get_src_line(), token.get_src_col())?;
+      // Only the tokens on the same line describe the position
+      if original.get_dst_line() != token.get_src_line() {
+        return None;
+      }
+      original.get_source_id()?;
+      Some(Token::new(
+        token.get_dst_line(),
+        token.get_dst_col(),
+        original.get_src_line(),
+        original.get_src_col(),
+        original.get_source_id(),
+        original.get_name_id(),
+      ))
+    })
+    .collect();
+  SourceMap::new(
+    map.get_file().cloned(),
+    input_map.get_names().cloned().collect(),
+    input_map.get_source_root().map(String::from),
+    input_map.get_sources().cloned().collect(),
+    input_map.get_source_contents().map(|content| content.cloned()).collect(),
+    tokens,
+    None,
+  )
+}
@@ -25,6 +25,7 @@ use crate::{
   dep::IncludedAtoms,
   folding::ConstantFolder,
   mangling::{Mangler, ManglingStats},
+  preparse::ParsedModule,
   utils::ExtraData,
 };
 
@@ -37,7 +38,8 @@ pub struct Transformer<'a> {
   pub conditional_data: &'a ConditionalDataMap<'a>,
   pub folder: &'a ConstantFolder<'a>,
   pub mangler: Rc<RefCell<&'a mut Mangler<'a>>>,
-  pub semantic: Semantic<'a>,
+  /// Owns the semantic data of the module
+  pub parsed: Rc<ParsedModule<'a>>,
   pub mangling_stats: Option<Rc<RefCell<ManglingStats>>>,
 
   pub ast: AstBuilder<'a>,
@@ -61,7 +63,7 @@ impl<'a> Transformer<'a> {
     conditional_data: &'a ConditionalDataMap<'a>,
     folder: &'a ConstantFolder<'a>,
     mangler: Rc<RefCell<&'a mut Mangler<'a>>>,
-    semantic: Semantic<'a>,
+    parsed: Rc<ParsedModule<'a>>,
     mangling_stats: Option<Rc<RefCell<ManglingStats>>>,
   ) -> Self {
     Transformer {
@@ -73,7 +75,7 @@ impl<'a> Transformer<'a> {
       conditional_data,
       folder,
       mangler,
-      semantic,
+      parsed,
       mangling_stats,
 
       ast: AstBuilder::new(allocator),
@@ -87,6 +89,10 @@ impl<'a> Transformer<'a> {
     }
   }
 
+  pub fn semantic(&self) -> &Semantic<'a> {
+    &self.parsed.semantic
+  }
+
   pub fn transform_program(&self, node: &'a Program<'a>) -> Program<'a> {
     let Program { span, source_type, source_text, comments, hashbang, directives, body, .. } = node;
 
@@ -119,7 +125,7 @@ impl<'a> Transformer<'a> {
   }
 
   pub fn update_var_decl_state(&self, symbol: SymbolId, is_declaration: bool) {
-    if !self.semantic.scoping().symbol_flags(symbol).is_function_scoped_declaration() {
+    if !self.semantic().scoping().symbol_flags(symbol).is_function_scoped_declaration() {
       return;
     }
     let mut var_decls = self.var_decls.borrow_mut();
@@ -136,7 +142,7 @@ impl<'a> Transformer<'a> {
     scope_id: ScopeId,
     statements: &mut oxc::allocator::Vec<'a, Statement<'a>>,
   ) {
-    let bindings = self.semantic.scoping().get_bindings(scope_id);
+    let bindings = self.semantic().scoping().get_bindings(scope_id);
     if bindings.is_empty() {
       return;
     }
@@ -145,8 +151,8 @@ impl<'a> Transformer<'a> {
     let mut declarations = self.ast.vec();
     for symbol_id in bindings.values() {
       if var_decls.get(symbol_id) == Some(&true) {
-        let name = self.semantic.scoping().symbol_name(*symbol_id);
-        let span = self.semantic.scoping().symbol_span(*symbol_id);
+        let name = self.semantic().scoping().symbol_name(*symbol_id);
+        let span = self.semantic().scoping().symbol_span(*symbol_id);
         declarations.push(self.ast.variable_declarator(
           span,
           VariableDeclarationKind::Var,
@@ -175,7 +181,7 @@ impl<'a> Transformer<'a> {
   }
 
   pub fn build_unused_binding_identifier(&self, span: Span) -> BindingIdentifier<'a> {
-    let text = self.semantic.source_text().as_bytes();
+    let text = self.semantic().source_text().as_bytes();
     let start = 5.max(span.start as usize) - 5;
     let end = text.len().min(span.end as usize + 5);
 
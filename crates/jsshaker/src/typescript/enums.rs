@@ -0,0 +1,275 @@
+use oxc::{
+  allocator::TakeIn,
+  ast::{
+    NONE,
+    ast::{
+      BinaryOperator, Declaration, Expression, IdentifierReference, ObjectPropertyKind,
+      PropertyKey, PropertyKind, TSEnumDeclaration, TSEnumMemberName, UnaryOperator,
+      VariableDeclarationKind,
+    },
+  },
+  span::{Atom, SPAN},
+  syntax::number::NumberBase,
+};
+use oxc_ast_visit::Visit;
+use oxc_ecmascript::ToInt32;
+use oxc_syntax::number::ToJsString;
+use rustc_hash::FxHashMap;
+
+use super::TypeScriptStripper;
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum EnumValue<'a> {
+  Number(f64),
+  String(Atom<'a>),
+}
+
+impl<'a> TypeScriptStripper<'a> {
+  /// `enum E { A, B = "b" }` -> `var E = { A: 0, "0": "A", B: "b" }`
+  ///
+  /// Merged declarations assign to the existing object: `var E = Object.assign(E, { C: 1 })`
+  pub(super) fn lower_enum(&mut self, node: &mut TSEnumDeclaration<'a>) -> Declaration<'a> {
+    let enum_name = node.id.name;
+    let symbol = node.id.symbol_id.get();
+    let is_merged = symbol.is_some_and(|symbol| !self.declared_merged.insert(symbol));
+    let mut members =
+      symbol.and_then(|symbol| self.enum_members.remove(&symbol)).unwrap_or_default();
+    let mut properties = self.ast.vec();
+    let mut next = Some(0.0);
+
+    for member in node.body.members.iter_mut() {
+      let name = match &member.id {
+        TSEnumMemberName::Identifier(id) => id.name,
+        TSEnumMemberName::String(s) | TSEnumMemberName::ComputedString(s) => s.value,
+        TSEnumMemberName::ComputedTemplateString(t) => {
+          t.single_quasi().unwrap_or_else(|| Atom::from(""))
+        }
+      };
+
+      let value = match &member.initializer {
+        Some(init) => self.eval_enum_member(init, enum_name, &members),
+        None => next.map(EnumValue::Number),
+      };
+
+      let value_expr = match value {
+        Some(EnumValue::Number(n)) => {
+          self.ast.expression_numeric_literal(member.span, n, None, NumberBase::Decimal)
+        }
+        Some(EnumValue::String(s)) => self.ast.expression_string_literal(member.span, s, None),
+        None => match &mut member.initializer {
+          Some(init) => {
+            // Other members are bound in the enum scope, and the enum object doesn't exist yet
+            // unless it is merged
+            if references_any(init, |reference| {
+              members.contains_key(reference) || !is_merged && reference == enum_name.as_str()
+            }) {
+              self.unsupported(
+                member.span,
+                format!(
+                  "Non-constant initializer of enum member `{enum_name}.{name}` referencing the enum is not supported",
+                ),
+              );
+            } else {
+              self.diagnostics.push((
+                member.span,
+                format!(
+                  "Initializer of enum member `{enum_name}.{name}` is not a constant expression, it is evaluated outside of the enum scope",
+                ),
+              ));
+            }
+            init.take_in(self.ast)
+          }
+          None => {
+            self.diagnostics.push((
+              member.span,
+              format!("Enum member `{enum_name}.{name}` must have an initializer"),
+            ));
+            self.ast.void_0(member.span)
+          }
+        },
+      };
+
+      properties.push(self.ast.object_property_kind_object_property(
+        member.span,
+        PropertyKind::Init,
+        self.property_key(name),
+        value_expr,
+        false,
+        false,
+        false,
+      ));
+
+      // Only numeric members have reverse mappings
+      if let Some(EnumValue::Number(n)) = value {
+        let key = n.to_js_string();
+        properties.push(self.reverse_mapping(key, name));
+      }
+
+      next = match value {
+        Some(EnumValue::Number(n)) => Some(n + 1.0),
+        _ => None,
+      };
+      members.insert(name, value);
+    }
+
+    if let Some(symbol) = symbol {
+      self.enum_members.insert(symbol, members);
+    }
+    let mut init = self.ast.expression_object(node.body.span, properties);
+    if is_merged {
+      init = self.ast.expression_call(
+        node.span,
+        Expression::from(self.ast.member_expression_static(
+          SPAN,
+          self.ast.expression_identifier(SPAN, "Object"),
+          self.ast.identifier_name(SPAN, "assign"),
+          false,
+        )),
+        NONE,
+        self
+          .ast
+          .vec_from_array([self.ast.expression_identifier(SPAN, enum_name).into(), init.into()]),
+        false,
+      );
+    }
+    self.ast.declaration_variable(
+      node.span,
+      VariableDeclarationKind::Var,
+      self.ast.vec1(self.ast.variable_declarator(
+        node.span,
+        VariableDeclarationKind::Var,
+        self.ast.binding_pattern_binding_identifier(node.id.span, enum_name),
+        NONE,
+        Some(init),
+        false,
+      )),
+      false,
+    )
+  }
+
+  fn property_key(&self, name: Atom<'a>) -> PropertyKey<'a> {
+    if oxc_syntax::identifier::is_identifier_name(&name) {
+      self.ast.property_key_static_identifier(SPAN, name)
+    } else {
+      PropertyKey::StringLiteral(self.ast.alloc_string_literal(SPAN, name, None))
+    }
+  }
+
+  fn reverse_mapping(&self, key: String, name: Atom<'a>) -> ObjectPropertyKind<'a> {
+    self.ast.object_property_kind_object_property(
+      SPAN,
+      PropertyKind::Init,
+      PropertyKey::StringLiteral(self.ast.alloc_string_literal(SPAN, self.ast.atom(&key), None)),
+      self.ast.expression_string_literal(SPAN, name, None),
+      false,
+      false,
+      false,
+    )
+  }
+
+  fn eval_enum_member(
+    &self,
+    expr: &Expression<'a>,
+    enum_name: Atom<'a>,
+    members: &FxHashMap<Atom<'a>, Option<EnumValue<'a>>>,
+  ) -> Option<EnumValue<'a>> {
+    match expr {
+      Expression::NumericLiteral(node) => Some(EnumValue::Number(node.value)),
+      Expression::StringLiteral(node) => Some(EnumValue::String(node.value)),
+      Expression::TemplateLiteral(node) => node.single_quasi().map(EnumValue::String),
+      Expression::ParenthesizedExpression(node) => {
+        self.eval_enum_member(&node.expression, enum_name, members)
+      }
+      Expression::Identifier(node) => match node.name.as_str() {
+        "Infinity" => Some(EnumValue::Number(f64::INFINITY)),
+        "NaN" => Some(EnumValue::Number(f64::NAN)),
+        _ => members.get(&node.name).copied().flatten(),
+      },
+      Expression::StaticMemberExpression(node) => match &node.object {
+        Expression::Identifier(object) if object.name == enum_name => {
+          members.get(&node.property.name).copied().flatten()
+        }
+        _ => None,
+      },
+      Expression::ComputedMemberExpression(node) => match (&node.object, &node.expression) {
+        (Expression::Identifier(object), Expression::StringLiteral(key))
+          if object.name == enum_name =>
+        {
+          members.get(&key.value).copied().flatten()
+        }
+        _ => None,
+      },
+      Expression::UnaryExpression(node) => {
+        let EnumValue::Number(value) = self.eval_enum_member(&node.argument, enum_name, members)?
+        else {
+          return None;
+        };
+        Some(EnumValue::Number(match node.operator {
+          UnaryOperator::UnaryPlus => value,
+          UnaryOperator::UnaryNegation => -value,
+          UnaryOperator::BitwiseNot => f64::from(!to_int32(value)),
+          _ => return None,
+        }))
+      }
+      Expression::BinaryExpression(node) => {
+        let left = self.eval_enum_member(&node.left, enum_name, members)?;
+        let right = self.eval_enum_member(&node.right, enum_name, members)?;
+        match (left, right) {
+          (EnumValue::Number(l), EnumValue::Number(r)) => {
+            Some(EnumValue::Number(match node.operator {
+              BinaryOperator::Addition => l + r,
+              BinaryOperator::Subtraction => l - r,
+              BinaryOperator::Multiplication => l * r,
+              BinaryOperator::Division => l / r,
+              BinaryOperator::Remainder => l % r,
+              BinaryOperator::Exponential => l.powf(r),
+              BinaryOperator::BitwiseOR => f64::from(to_int32(l) | to_int32(r)),
+              BinaryOperator::BitwiseAnd => f64::from(to_int32(l) & to_int32(r)),
+              BinaryOperator::BitwiseXOR => f64::from(to_int32(l) ^ to_int32(r)),
+              BinaryOperator::ShiftLeft => f64::from(to_int32(l) << (to_int32(r) & 31)),
+              BinaryOperator::ShiftRight => f64::from(to_int32(l) >> (to_int32(r) & 31)),
+              BinaryOperator::ShiftRightZeroFill => {
+                f64::from((to_int32(l) as u32) >> (to_int32(r) & 31))
+              }
+              _ => return None,
+            }))
+          }
+          (l, r) if node.operator == BinaryOperator::Addition => {
+            let l = self.enum_value_to_string(l);
+            let r = self.enum_value_to_string(r);
+            Some(EnumValue::String(self.ast.atom_from_strs_array([l.as_str(), r.as_str()])))
+          }
+          _ => None,
+        }
+      }
+      _ => None,
+    }
+  }
+
+  fn enum_value_to_string(&self, value: EnumValue<'a>) -> Atom<'a> {
+    match value {
+      EnumValue::Number(n) => self.ast.atom(&n.to_js_string()),
+      EnumValue::String(s) => s,
+    }
+  }
+}
+
+fn to_int32(value: f64) -> i32 {
+  value.to_int_32()
+}
+
+/// Whether the expression references a name matching `is_target`
+fn references_any(expr: &Expression, is_target: impl Fn(&str) -> bool) -> bool {
+  struct Finder<F> {
+    is_target: F,
+    found: bool,
+  }
+  impl<'a, F: Fn(&str) -> bool> Visit<'a> for Finder<F> {
+    fn visit_identifier_reference(&mut self, node: &IdentifierReference<'a>) {
+      self.found |= (self.is_target)(node.name.as_str());
+    }
+  }
+  let mut finder = Finder { is_target, found: false };
+  finder.visit_expression(expr);
+  finder.found
+}
@@ -0,0 +1,590 @@
+mod enums;
+mod namespaces;
+
+use std::mem;
+
+use oxc::{
+  allocator::{self, Allocator, TakeIn},
+  ast::{
+    AstBuilder, NONE,
+    ast::{
+      AccessorProperty, ArrowFunctionExpression, AssignmentOperator, AssignmentTarget,
+      AssignmentTargetPropertyIdentifier, CallExpression, CatchParameter, ChainElement, Class,
+      ClassElement, Declaration, ExportDefaultDeclarationKind, Expression, FormalParameter,
+      Function, IdentifierReference, ImportDeclaration, ImportDeclarationSpecifier,
+      JSXOpeningElement, MemberExpression, MethodDefinition, MethodDefinitionKind,
+      MethodDefinitionType, ModuleExportName, NewExpression, ObjectProperty, Program,
+      PropertyDefinition, PropertyDefinitionType, SimpleAssignmentTarget, Statement,
+      TSImportEqualsDeclaration, TSModuleDeclaration, TSModuleDeclarationBody, TSModuleReference,
+      TSTypeName, TaggedTemplateExpression, VariableDeclarationKind, VariableDeclarator,
+    },
+    match_declaration,
+  },
+  semantic::{Scoping, SemanticBuilder, SymbolId},
+  span::{Atom, GetSpan, SPAN, Span},
+};
+use oxc_ast_visit::{VisitMut, walk_mut};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use self::{enums::EnumValue, namespaces::is_runtime_namespace};
+
+/// Spans and messages of the TypeScript syntax that can't be erased
+pub type TypeScriptErrors = Vec<(Span, String)>;
+
+/// Erases TypeScript-only syntax so that the rest of the pipeline only sees JavaScript.
+///
+/// Type annotations, type-only declarations and type-only imports/exports are removed,
+/// enums are lowered to plain objects, namespaces are lowered to IIFEs, and
+/// `import x = require()`/`export =` are lowered to their CommonJS equivalents.
+///
+/// Returns the diagnostics for the syntax that can't be erased. If the module can't be
+/// transformed without changing its behavior, returns `Err` with the diagnostics instead,
+/// and the module should be left untransformed.
+pub fn strip_typescript<'a>(
+  allocator: &'a Allocator,
+  program: &mut Program<'a>,
+) -> Result<TypeScriptErrors, TypeScriptErrors> {
+  let scoping = SemanticBuilder::new().build(program).semantic.into_scoping();
+  let mut stripper = TypeScriptStripper {
+    ast: AstBuilder::new(allocator),
+    scoping,
+    namespace_exports: FxHashMap::default(),
+    declared_merged: FxHashSet::default(),
+    enum_members: FxHashMap::default(),
+    diagnostics: Vec::new(),
+    is_unsupported: false,
+  };
+  stripper.visit_program(program);
+  program.source_type = program.source_type.with_javascript(true);
+  if stripper.is_unsupported { Err(stripper.diagnostics) } else { Ok(stripper.diagnostics) }
+}
+
+struct TypeScriptStripper<'a> {
+  ast: AstBuilder<'a>,
+  /// Scoping of the original TypeScript program, used to tell type-only bindings apart
+  scoping: Scoping,
+  /// Exported variables of namespaces, whose references are rewritten to `N.x`
+  namespace_exports: FxHashMap<SymbolId, Atom<'a>>,
+  /// Namespaces and enums whose binding is already declared, e.g. by a merged declaration
+  declared_merged: FxHashSet<SymbolId>,
+  /// Members of the enums declared so far, shared by the merged declarations.
+  /// The values of the non-constant members are `None`.
+  enum_members: FxHashMap<SymbolId, FxHashMap<Atom<'a>, Option<EnumValue<'a>>>>,
+  diagnostics: Vec<(Span, String)>,
+  is_unsupported: bool,
+}
+
+impl<'a> TypeScriptStripper<'a> {
+  fn is_value_symbol(&self, symbol: Option<SymbolId>) -> bool {
+    symbol.is_none_or(|symbol| self.scoping.symbol_flags(symbol).is_value())
+  }
+
+  /// `x` -> `N.x`, if `x` is exported from the namespace `N`
+  fn namespace_export_reference(
+    &self,
+    id: &IdentifierReference<'a>,
+  ) -> Option<MemberExpression<'a>> {
+    let symbol = self.scoping.get_reference(id.reference_id.get()?).symbol_id()?;
+    let namespace = self.namespace_exports.get(&symbol)?;
+    Some(self.namespace_member(*namespace, id.name))
+  }
+
+  fn is_value_referenced(&self, symbol: Option<SymbolId>) -> bool {
+    symbol.is_none_or(|symbol| {
+      self
+        .scoping
+        .get_resolved_references(symbol)
+        .any(|r| r.is_value() && !r.flags().is_value_as_type())
+    })
+  }
+
+  fn strip_statement(&mut self, node: Statement<'a>) -> Option<Statement<'a>> {
+    match node {
+      match_declaration!(Statement) => {
+        self.strip_declaration(node.into_declaration()).map(Into::into)
+      }
+      Statement::ImportDeclaration(mut node) => {
+        self.strip_import_declaration(&mut node).then_some(Statement::ImportDeclaration(node))
+      }
+      Statement::ExportNamedDeclaration(mut node) => {
+        if node.export_kind.is_type() {
+          return None;
+        }
+        if let Some(declaration) = node.declaration.take() {
+          let is_merged_enum = matches!(
+            &declaration,
+            Declaration::TSEnumDeclaration(declaration)
+              if declaration.id.symbol_id.get().is_some_and(|s| self.declared_merged.contains(&s))
+          );
+          let declaration = self.strip_declaration(declaration)?;
+          if is_merged_enum {
+            // Merged enums assign to the binding, which is already exported
+            return Some(declaration.into());
+          }
+          node.declaration = Some(declaration);
+        } else {
+          let had_specifiers = !node.specifiers.is_empty();
+          let is_reexport = node.source.is_some();
+          node.specifiers.retain(|specifier| {
+            if specifier.export_kind.is_type() {
+              return false;
+            }
+            // Re-exported bindings from other modules can't be checked here
+            if is_reexport {
+              return true;
+            }
+            match &specifier.local {
+              ModuleExportName::IdentifierReference(id) => {
+                let reference = self.scoping.get_reference(id.reference_id());
+                self.is_value_symbol(reference.symbol_id())
+              }
+              _ => true,
+            }
+          });
+          if had_specifiers && node.specifiers.is_empty() {
+            return None;
+          }
+        }
+        Some(Statement::ExportNamedDeclaration(node))
+      }
+      Statement::ExportDefaultDeclaration(node) => match &node.declaration {
+        ExportDefaultDeclarationKind::TSInterfaceDeclaration(_) => None,
+        ExportDefaultDeclarationKind::FunctionDeclaration(function) if function.body.is_none() => {
+          None
+        }
+        ExportDefaultDeclarationKind::Identifier(id) => {
+          let reference = self.scoping.get_reference(id.reference_id());
+          self
+            .is_value_symbol(reference.symbol_id())
+            .then_some(Statement::ExportDefaultDeclaration(node))
+        }
+        _ => Some(Statement::ExportDefaultDeclaration(node)),
+      },
+      Statement::ExportAllDeclaration(node) => {
+        (!node.export_kind.is_type()).then_some(Statement::ExportAllDeclaration(node))
+      }
+      Statement::TSExportAssignment(mut node) => {
+        // `export = value` -> `module.exports = value`
+        let span = node.span;
+        let target = self.ast.member_expression_static(
+          SPAN,
+          self.ast.expression_identifier(SPAN, "module"),
+          self.ast.identifier_name(SPAN, "exports"),
+          false,
+        );
+        Some(self.ast.statement_expression(
+          span,
+          self.ast.expression_assignment(
+            span,
+            AssignmentOperator::Assign,
+            AssignmentTarget::from(SimpleAssignmentTarget::from(target)),
+            node.expression.take_in(self.ast),
+          ),
+        ))
+      }
+      Statement::TSNamespaceExportDeclaration(_) => None,
+      node => Some(node),
+    }
+  }
+
+  fn strip_declaration(&mut self, node: Declaration<'a>) -> Option<Declaration<'a>> {
+    match node {
+      Declaration::VariableDeclaration(node) => {
+        (!node.declare).then_some(Declaration::VariableDeclaration(node))
+      }
+      Declaration::FunctionDeclaration(node) => {
+        // Ambient functions and overload signatures have no body
+        (!node.declare && node.body.is_some()).then_some(Declaration::FunctionDeclaration(node))
+      }
+      Declaration::ClassDeclaration(node) => {
+        (!node.declare).then_some(Declaration::ClassDeclaration(node))
+      }
+      Declaration::TSTypeAliasDeclaration(_)
+      | Declaration::TSInterfaceDeclaration(_)
+      | Declaration::TSGlobalDeclaration(_) => None,
+      Declaration::TSEnumDeclaration(mut node) => {
+        (!node.declare).then(|| self.lower_enum(&mut node))
+      }
+      Declaration::TSModuleDeclaration(node) => {
+        // Runtime namespaces are lowered in `visit_statements`
+        if !node.declare && !is_type_only_namespace(&node) {
+          self.unsupported(
+            node.span,
+            format!("TypeScript module `{}` with runtime values is not supported", node.id),
+          );
+        }
+        None
+      }
+      Declaration::TSImportEqualsDeclaration(mut node) => self.lower_import_equals(&mut node),
+    }
+  }
+
+  /// Returns `false` if the whole declaration should be removed.
+  fn strip_import_declaration(&mut self, node: &mut ImportDeclaration<'a>) -> bool {
+    if node.import_kind.is_type() {
+      return false;
+    }
+    let Some(specifiers) = &mut node.specifiers else {
+      // Side-effect only import
+      return true;
+    };
+    if specifiers.is_empty() {
+      // `import {} from 'x'` is kept as a side-effect import
+      return true;
+    }
+    specifiers.retain(|specifier| {
+      if let ImportDeclarationSpecifier::ImportSpecifier(specifier) = specifier
+        && specifier.import_kind.is_type()
+      {
+        return false;
+      }
+      // Same as TypeScript: imports only used as types are elided
+      self.is_value_referenced(specifier.local().symbol_id.get())
+    });
+    !specifiers.is_empty()
+  }
+
+  fn lower_import_equals(
+    &mut self,
+    node: &mut TSImportEqualsDeclaration<'a>,
+  ) -> Option<Declaration<'a>> {
+    if node.import_kind.is_type() || !self.is_value_referenced(node.id.symbol_id.get()) {
+      return None;
+    }
+    let (kind, init) = match &mut node.module_reference {
+      TSModuleReference::ExternalModuleReference(reference) => (
+        // `import x = require('y')` -> `const x = require('y')`
+        VariableDeclarationKind::Const,
+        self.ast.expression_call(
+          reference.span,
+          self.ast.expression_identifier(SPAN, "require"),
+          NONE,
+          self.ast.vec1(
+            Expression::StringLiteral(self.ast.alloc(reference.expression.take_in(self.ast)))
+              .into(),
+          ),
+          false,
+        ),
+      ),
+      // `import x = A.B` -> `var x = A.B`
+      name => {
+        (VariableDeclarationKind::Var, self.ts_type_name_to_expression(name.to_ts_type_name()))
+      }
+    };
+    Some(self.ast.declaration_variable(
+      node.span,
+      kind,
+      self.ast.vec1(self.ast.variable_declarator(
+        node.span,
+        kind,
+        self.ast.binding_pattern_binding_identifier(node.id.span, node.id.name),
+        NONE,
+        Some(init),
+        false,
+      )),
+      false,
+    ))
+  }
+
+  fn ts_type_name_to_expression(&self, name: &TSTypeName<'a>) -> Expression<'a> {
+    match name {
+      TSTypeName::IdentifierReference(id) => self.ast.expression_identifier(id.span, id.name),
+      TSTypeName::QualifiedName(name) => Expression::from(self.ast.member_expression_static(
+        name.span,
+        self.ts_type_name_to_expression(&name.left),
+        name.right.clone(),
+        false,
+      )),
+      TSTypeName::ThisExpression(node) => self.ast.expression_this(node.span),
+    }
+  }
+
+  /// `constructor(private a) {}` -> `constructor(a) { this.a = a; }`
+  fn lower_parameter_properties(&self, node: &mut MethodDefinition<'a>) {
+    let mut assignments = vec![];
+    for param in &node.value.params.items {
+      if param.accessibility.is_none() && !param.readonly && !param.r#override {
+        continue;
+      }
+      let Some(id) = param.pattern.get_binding_identifier() else {
+        continue;
+      };
+      let target = self.ast.member_expression_static(
+        id.span,
+        self.ast.expression_this(SPAN),
+        self.ast.identifier_name(id.span, id.name),
+        false,
+      );
+      assignments.push(self.ast.statement_expression(
+        id.span,
+        self.ast.expression_assignment(
+          id.span,
+          AssignmentOperator::Assign,
+          AssignmentTarget::from(SimpleAssignmentTarget::from(target)),
+          self.ast.expression_identifier(id.span, id.name),
+        ),
+      ));
+    }
+    if assignments.is_empty() {
+      return;
+    }
+    let Some(body) = &mut node.value.body else {
+      return;
+    };
+    // Parameter properties are initialized right after `super()`
+    let index = body
+      .statements
+      .iter()
+      .position(|statement| {
+        matches!(statement, Statement::ExpressionStatement(node)
+          if matches!(&node.expression, Expression::CallExpression(call) if call.callee.is_super()))
+      })
+      .map_or(0, |index| index + 1);
+    let rest = body.statements.split_off(index);
+    body.statements.extend(assignments);
+    body.statements.extend(rest);
+  }
+}
+
+impl<'a> VisitMut<'a> for TypeScriptStripper<'a> {
+  fn visit_statements(&mut self, it: &mut allocator::Vec<'a, Statement<'a>>) {
+    let statements = mem::replace(it, self.ast.vec());
+    for statement in statements {
+      match statement {
+        Statement::TSModuleDeclaration(mut node) if is_runtime_namespace(&node) => {
+          self.lower_namespace(&mut node, false, None, it);
+        }
+        Statement::ExportNamedDeclaration(mut node)
+          if matches!(&node.declaration, Some(Declaration::TSModuleDeclaration(namespace))
+            if is_runtime_namespace(namespace)) =>
+        {
+          let Some(Declaration::TSModuleDeclaration(mut namespace)) = node.declaration.take()
+          else {
+            unreachable!();
+          };
+          self.lower_namespace(&mut namespace, true, None, it);
+        }
+        statement => {
+          if let Some(statement) = self.strip_statement(statement) {
+            it.push(statement);
+          }
+        }
+      }
+    }
+    walk_mut::walk_statements(self, it);
+  }
+
+  fn visit_variable_declarator(&mut self, it: &mut VariableDeclarator<'a>) {
+    it.type_annotation = None;
+    it.definite = false;
+    walk_mut::walk_variable_declarator(self, it);
+  }
+
+  fn visit_catch_parameter(&mut self, it: &mut CatchParameter<'a>) {
+    it.type_annotation = None;
+    walk_mut::walk_catch_parameter(self, it);
+  }
+
+  fn visit_formal_parameter(&mut self, it: &mut FormalParameter<'a>) {
+    it.type_annotation = None;
+    it.optional = false;
+    it.accessibility = None;
+    it.readonly = false;
+    it.r#override = false;
+    walk_mut::walk_formal_parameter(self, it);
+  }
+
+  fn visit_function(&mut self, it: &mut Function<'a>, flags: oxc::semantic::ScopeFlags) {
+    it.type_parameters = None;
+    it.this_param = None;
+    it.return_type = None;
+    walk_mut::walk_function(self, it, flags);
+  }
+
+  fn visit_arrow_function_expression(&mut self, it: &mut ArrowFunctionExpression<'a>) {
+    it.type_parameters = None;
+    it.return_type = None;
+    walk_mut::walk_arrow_function_expression(self, it);
+  }
+
+  fn visit_class(&mut self, it: &mut Class<'a>) {
+    it.type_parameters = None;
+    it.super_type_arguments = None;
+    it.implements.clear();
+    it.r#abstract = false;
+    it.body.body.retain(|element| match element {
+      ClassElement::MethodDefinition(node) => {
+        node.r#type != MethodDefinitionType::TSAbstractMethodDefinition && node.value.body.is_some()
+      }
+      ClassElement::PropertyDefinition(node) => {
+        node.r#type != PropertyDefinitionType::TSAbstractPropertyDefinition && !node.declare
+      }
+      ClassElement::AccessorProperty(node) => !node.r#type.is_abstract(),
+      ClassElement::TSIndexSignature(_) => false,
+      ClassElement::StaticBlock(_) => true,
+    });
+    for element in it.body.body.iter_mut() {
+      if let ClassElement::MethodDefinition(node) = element
+        && node.kind == MethodDefinitionKind::Constructor
+      {
+        self.lower_parameter_properties(node);
+      }
+    }
+    walk_mut::walk_class(self, it);
+  }
+
+  fn visit_method_definition(&mut self, it: &mut MethodDefinition<'a>) {
+    it.accessibility = None;
+    it.optional = false;
+    it.r#override = false;
+    walk_mut::walk_method_definition(self, it);
+  }
+
+  fn visit_property_definition(&mut self, it: &mut PropertyDefinition<'a>) {
+    it.type_annotation = None;
+    it.accessibility = None;
+    it.optional = false;
+    it.definite = false;
+    it.readonly = false;
+    it.r#override = false;
+    walk_mut::walk_property_definition(self, it);
+  }
+
+  fn visit_accessor_property(&mut self, it: &mut AccessorProperty<'a>) {
+    it.type_annotation = None;
+    it.accessibility = None;
+    it.definite = false;
+    it.r#override = false;
+    walk_mut::walk_accessor_property(self, it);
+  }
+
+  fn visit_call_expression(&mut self, it: &mut CallExpression<'a>) {
+    it.type_arguments = None;
+    walk_mut::walk_call_expression(self, it);
+  }
+
+  fn visit_new_expression(&mut self, it: &mut NewExpression<'a>) {
+    it.type_arguments = None;
+    walk_mut::walk_new_expression(self, it);
+  }
+
+  fn visit_tagged_template_expression(&mut self, it: &mut TaggedTemplateExpression<'a>) {
+    it.type_arguments = None;
+    walk_mut::walk_tagged_template_expression(self, it);
+  }
+
+  fn visit_jsx_opening_element(&mut self, it: &mut JSXOpeningElement<'a>) {
+    it.type_arguments = None;
+    walk_mut::walk_jsx_opening_element(self, it);
+  }
+
+  fn visit_expression(&mut self, it: &mut Expression<'a>) {
+    // `a!` -> `a`, so that `a?.b!.c` stays a single optional chain.
+    // Other expression wrappers are handled by the analyzer.
+    while let Expression::TSNonNullExpression(node) = it {
+      *it = node.expression.take_in(self.ast);
+    }
+    if let Expression::Identifier(id) = it
+      && let Some(member) = self.namespace_export_reference(id)
+    {
+      *it = Expression::from(member);
+      return;
+    }
+    walk_mut::walk_expression(self, it);
+  }
+
+  fn visit_chain_element(&mut self, it: &mut ChainElement<'a>) {
+    if let ChainElement::TSNonNullExpression(node) = it {
+      let mut expression = node.expression.take_in(self.ast);
+      self.visit_expression(&mut expression);
+      *it = match expression {
+        Expression::CallExpression(node) => ChainElement::CallExpression(node),
+        expression if expression.is_member_expression() => {
+          ChainElement::from(expression.into_member_expression())
+        }
+        expression => {
+          self
+            .diagnostics
+            .push((expression.span(), "Unsupported TypeScript optional chain".to_string()));
+          ChainElement::TSNonNullExpression(
+            self.ast.alloc_ts_non_null_expression(expression.span(), expression),
+          )
+        }
+      };
+      return;
+    }
+    walk_mut::walk_chain_element(self, it);
+  }
+
+  fn visit_simple_assignment_target(&mut self, it: &mut SimpleAssignmentTarget<'a>) {
+    if let SimpleAssignmentTarget::AssignmentTargetIdentifier(id) = it
+      && let Some(member) = self.namespace_export_reference(id)
+    {
+      *it = SimpleAssignmentTarget::from(member);
+      return;
+    }
+    // `(a as any) = 1` -> `a = 1`
+    if let Some(expression) = it.get_expression_mut() {
+      let expression = expression.get_inner_expression_mut();
+      if !matches!(expression, Expression::Identifier(_)) && !expression.is_member_expression() {
+        self.unsupported(expression.span(), "Unsupported TypeScript assignment target".to_string());
+        return;
+      }
+      let mut expression = expression.take_in(self.ast);
+      self.visit_expression(&mut expression);
+      *it = match expression {
+        Expression::Identifier(node) => SimpleAssignmentTarget::AssignmentTargetIdentifier(node),
+        expression => SimpleAssignmentTarget::from(expression.into_member_expression()),
+      };
+      return;
+    }
+    walk_mut::walk_simple_assignment_target(self, it);
+  }
+
+  fn visit_object_property(&mut self, it: &mut ObjectProperty<'a>) {
+    walk_mut::walk_object_property(self, it);
+    // `{ x }` -> `{ x: N.x }`
+    if it.shorthand && !matches!(it.value, Expression::Identifier(_)) {
+      it.shorthand = false;
+    }
+  }
+
+  fn visit_assignment_target_property_identifier(
+    &mut self,
+    it: &mut AssignmentTargetPropertyIdentifier<'a>,
+  ) {
+    if self.namespace_export_reference(&it.binding).is_some() {
+      self.unsupported(
+        it.span,
+        "Destructuring assignments to exports of TypeScript namespaces are not supported".into(),
+      );
+    }
+    walk_mut::walk_assignment_target_property_identifier(self, it);
+  }
+}
+
+fn is_type_only_namespace(node: &TSModuleDeclaration) -> bool {
+  match &node.body {
+    None => true,
+    Some(TSModuleDeclarationBody::TSModuleDeclaration(node)) => is_type_only_namespace(node),
+    Some(TSModuleDeclarationBody::TSModuleBlock(block)) => block.body.iter().all(|statement| {
+      let declaration = match statement {
+        Statement::ExportNamedDeclaration(node) => match &node.declaration {
+          Some(declaration) => declaration,
+          None => return node.export_kind.is_type(),
+        },
+        match_declaration!(Statement) => statement.to_declaration(),
+        _ => return false,
+      };
+      match declaration {
+        Declaration::TSTypeAliasDeclaration(_) | Declaration::TSInterfaceDeclaration(_) => true,
+        Declaration::TSModuleDeclaration(node) => node.declare || is_type_only_namespace(node),
+        Declaration::VariableDeclaration(node) => node.declare,
+        Declaration::FunctionDeclaration(node) => node.declare,
+        Declaration::ClassDeclaration(node) => node.declare,
+        Declaration::TSEnumDeclaration(node) => node.declare,
+        _ => false,
+      }
+    }),
+  }
+}
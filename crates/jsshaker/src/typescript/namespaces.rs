@@ -0,0 +1,277 @@
+use oxc::{
+  allocator::{self, TakeIn},
+  ast::{
+    NONE,
+    ast::{
+      AssignmentOperator, AssignmentTarget, BindingPattern, Declaration, Expression,
+      FormalParameterKind, FunctionType, ImportOrExportKind, LogicalOperator, MemberExpression,
+      SimpleAssignmentTarget, Statement, TSModuleDeclaration, TSModuleDeclarationBody,
+      TSModuleDeclarationName, VariableDeclarationKind,
+    },
+  },
+  semantic::SymbolFlags,
+  span::{Atom, SPAN, Span},
+};
+
+use super::{TypeScriptStripper, is_type_only_namespace};
+
+/// Namespaces which are not erased, i.e. not ambient and declaring runtime values
+pub(super) fn is_runtime_namespace(node: &TSModuleDeclaration) -> bool {
+  !node.declare
+    && matches!(node.id, TSModuleDeclarationName::Identifier(_))
+    && !is_type_only_namespace(node)
+}
+
+impl<'a> TypeScriptStripper<'a> {
+  /// `namespace N { export const x = 1; }` -> `var N; (function (N) { N.x = 1; })(N || (N = {}));`
+  ///
+  /// Same as TypeScript, references to the exported variables are rewritten to `N.x`.
+  /// `parent` is the namespace containing this one, if any.
+  pub(super) fn lower_namespace(
+    &mut self,
+    node: &mut TSModuleDeclaration<'a>,
+    is_export: bool,
+    parent: Option<Atom<'a>>,
+    statements: &mut allocator::Vec<'a, Statement<'a>>,
+  ) {
+    let TSModuleDeclarationName::Identifier(id) = &node.id else {
+      unreachable!("Not a runtime namespace");
+    };
+    let name = id.name;
+    let span = node.span;
+    if self.is_namespace_shadowed(node, name) {
+      self.unsupported(span, format!("TypeScript namespace `{name}` is shadowed in its body"));
+      return;
+    }
+
+    // Merged declarations share the binding, which is only declared once
+    let is_declared = id.symbol_id.get().is_some_and(|symbol| {
+      !self.declared_merged.insert(symbol)
+        || self
+          .scoping
+          .symbol_flags(symbol)
+          .intersects(SymbolFlags::Class | SymbolFlags::Function | SymbolFlags::RegularEnum)
+    });
+    if !is_declared {
+      let declaration = self.ast.declaration_variable(
+        span,
+        VariableDeclarationKind::Var,
+        self.ast.vec1(self.ast.variable_declarator(
+          span,
+          VariableDeclarationKind::Var,
+          self.ast.binding_pattern_binding_identifier(id.span, name),
+          NONE,
+          None,
+          false,
+        )),
+        false,
+      );
+      statements.push(if is_export && parent.is_none() {
+        Statement::ExportNamedDeclaration(self.ast.alloc_export_named_declaration(
+          span,
+          Some(declaration),
+          self.ast.vec(),
+          None,
+          ImportOrExportKind::Value,
+          NONE,
+        ))
+      } else {
+        declaration.into()
+      });
+    }
+
+    let mut directives = self.ast.vec();
+    let mut body = self.ast.vec();
+    match &mut node.body {
+      // `namespace A.B {}`
+      Some(TSModuleDeclarationBody::TSModuleDeclaration(inner)) => {
+        self.lower_namespace(inner, true, Some(name), &mut body);
+      }
+      Some(TSModuleDeclarationBody::TSModuleBlock(block)) => {
+        directives = block.directives.take_in(self.ast);
+        for statement in block.body.take_in(self.ast) {
+          self.lower_namespace_statement(statement, name, &mut body);
+        }
+      }
+      None => {}
+    }
+
+    // `N || (N = {})`, or `N = P.N || (P.N = {})` if exported from the namespace `P`
+    let argument = match parent.filter(|_| is_export) {
+      Some(parent) => self.ast.expression_assignment(
+        SPAN,
+        AssignmentOperator::Assign,
+        self.identifier_target(name),
+        self.ast.expression_logical(
+          SPAN,
+          Expression::from(self.namespace_member(parent, name)),
+          LogicalOperator::Or,
+          self.ast.expression_assignment(
+            SPAN,
+            AssignmentOperator::Assign,
+            AssignmentTarget::from(SimpleAssignmentTarget::from(
+              self.namespace_member(parent, name),
+            )),
+            self.ast.expression_object(SPAN, self.ast.vec()),
+          ),
+        ),
+      ),
+      None => self.ast.expression_logical(
+        SPAN,
+        self.ast.expression_identifier(SPAN, name),
+        LogicalOperator::Or,
+        self.ast.expression_assignment(
+          SPAN,
+          AssignmentOperator::Assign,
+          self.identifier_target(name),
+          self.ast.expression_object(SPAN, self.ast.vec()),
+        ),
+      ),
+    };
+    let function = self.ast.expression_function(
+      span,
+      FunctionType::FunctionExpression,
+      None,
+      false,
+      false,
+      false,
+      NONE,
+      NONE,
+      self.ast.alloc_formal_parameters(
+        SPAN,
+        FormalParameterKind::FormalParameter,
+        self.ast.vec1(self.ast.formal_parameter(
+          SPAN,
+          self.ast.vec(),
+          self.ast.binding_pattern_binding_identifier(SPAN, name),
+          NONE,
+          NONE,
+          false,
+          None,
+          false,
+          false,
+        )),
+        NONE,
+      ),
+      NONE,
+      Some(self.ast.alloc_function_body(SPAN, directives, body)),
+    );
+    statements.push(self.ast.statement_expression(
+      span,
+      self.ast.expression_call(span, function, NONE, self.ast.vec1(argument.into()), false),
+    ));
+  }
+
+  /// The other TypeScript syntax in the namespace body is erased when it is visited later
+  fn lower_namespace_statement(
+    &mut self,
+    statement: Statement<'a>,
+    namespace: Atom<'a>,
+    statements: &mut allocator::Vec<'a, Statement<'a>>,
+  ) {
+    let Statement::ExportNamedDeclaration(mut node) = statement else {
+      statements.push(statement);
+      return;
+    };
+    if node.export_kind.is_type() {
+      return;
+    }
+    let Some(declaration) = node.declaration.take() else {
+      // Not valid in namespaces
+      self.unsupported(node.span, "Export lists in TypeScript namespaces are not supported".into());
+      return;
+    };
+    match declaration {
+      Declaration::TSModuleDeclaration(mut node) if is_runtime_namespace(&node) => {
+        self.lower_namespace(&mut node, true, Some(namespace), statements);
+      }
+      Declaration::VariableDeclaration(mut node) if !node.declare => {
+        for declarator in node.declarations.iter_mut() {
+          let BindingPattern::BindingIdentifier(id) = &declarator.id else {
+            self.unsupported(
+              declarator.span,
+              "Destructuring exports of TypeScript namespaces are not supported".into(),
+            );
+            continue;
+          };
+          if let Some(symbol) = id.symbol_id.get() {
+            self.namespace_exports.insert(symbol, namespace);
+          }
+          // `export let x = 1` -> `N.x = 1`
+          if let Some(init) = declarator.init.take() {
+            statements.push(self.ast.statement_expression(
+              declarator.span,
+              self.ast.expression_assignment(
+                declarator.span,
+                AssignmentOperator::Assign,
+                AssignmentTarget::from(SimpleAssignmentTarget::from(
+                  self.namespace_member(namespace, id.name),
+                )),
+                init,
+              ),
+            ));
+          }
+        }
+      }
+      declaration => {
+        // Functions, classes, enums and `import x = ` are kept as local bindings, and then
+        // assigned to the namespace object
+        let Some(name) = declaration.id().map(|id| id.name) else {
+          return;
+        };
+        let Some(declaration) = self.strip_declaration(declaration) else {
+          return;
+        };
+        statements.push(declaration.into());
+        statements.push(self.ast.statement_expression(
+          SPAN,
+          self.ast.expression_assignment(
+            SPAN,
+            AssignmentOperator::Assign,
+            AssignmentTarget::from(SimpleAssignmentTarget::from(
+              self.namespace_member(namespace, name),
+            )),
+            self.ast.expression_identifier(SPAN, name),
+          ),
+        ));
+      }
+    }
+  }
+
+  /// The namespace parameter of the IIFE would be shadowed by a binding with the same name
+  fn is_namespace_shadowed(&self, node: &TSModuleDeclaration<'a>, name: Atom<'a>) -> bool {
+    let Some(scope) = node.scope_id.get() else {
+      return false;
+    };
+    self.scoping.symbol_ids().any(|symbol| {
+      self.scoping.symbol_name(symbol) == name.as_str()
+        && self.scoping.scope_ancestors(self.scoping.symbol_scope_id(symbol)).any(|s| s == scope)
+    })
+  }
+
+  /// `N.x`
+  pub(super) fn namespace_member(
+    &self,
+    namespace: Atom<'a>,
+    name: Atom<'a>,
+  ) -> MemberExpression<'a> {
+    self.ast.member_expression_static(
+      SPAN,
+      self.ast.expression_identifier(SPAN, namespace),
+      self.ast.identifier_name(SPAN, name),
+      false,
+    )
+  }
+
+  fn identifier_target(&self, name: Atom<'a>) -> AssignmentTarget<'a> {
+    AssignmentTarget::from(
+      self.ast.simple_assignment_target_assignment_target_identifier(SPAN, name),
+    )
+  }
+
+  /// The module is left untransformed
+  pub(super) fn unsupported(&mut self, span: Span, message: String) {
+    self.is_unsupported = true;
+    self.diagnostics.push((span, message));
+  }
+}
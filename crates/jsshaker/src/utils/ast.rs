@@ -169,6 +169,7 @@ ast_kind_2! {
   ArrowFunctionBodyExecuted(&'a FunctionBody<'a>),
   WithDefault(&'a Expression<'a>),
   FunctionNoShake(&'a Function<'a>),
+  TopLevelAwait(&'a AwaitExpression<'a>),
   JSXIdentifier(&'a JSXIdentifier<'a>),
   SuperExpr(&'a Expression<'a>),
 }
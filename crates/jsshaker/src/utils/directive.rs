@@ -0,0 +1,16 @@
+use oxc::ast::ast::{Directive, FunctionBody, Program};
+
+fn has_directive(directives: &[Directive], name: &str) -> bool {
+  directives.iter().any(|directive| directive.directive == name)
+}
+
+/// Server Actions (`"use server"` functions) are called by the framework with unknown arguments
+pub fn is_server_function(body: Option<&FunctionBody>) -> bool {
+  body.is_some_and(|body| has_directive(&body.directives, "use server"))
+}
+
+/// All exports of `"use client"`/`"use server"` modules are referenced by the framework
+pub fn is_rsc_boundary_module(program: &Program) -> bool {
+  has_directive(&program.directives, "use client")
+    || has_directive(&program.directives, "use server")
+}
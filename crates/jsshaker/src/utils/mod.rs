@@ -3,6 +3,7 @@ pub mod ast;
 pub mod box_bump;
 mod callee_info;
 mod data;
+mod directive;
 pub mod effect_builder;
 mod escape_template_element_value;
 mod f64_with_eq;
@@ -13,10 +14,13 @@ pub mod ptr_idx;
 pub mod skip_hash_eq;
 pub mod snapshot_vec;
 mod symbol_id;
+mod top_level_await;
 pub mod version;
 
 pub use callee_info::*;
 pub use data::*;
+pub use directive::*;
 pub use f64_with_eq::*;
 pub use found::*;
 pub use get_two_mut::*;
+pub use top_level_await::*;
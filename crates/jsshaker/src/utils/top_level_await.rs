@@ -0,0 +1,48 @@
+use oxc::{
+  ast::ast::{
+    ArrowFunctionExpression, AwaitExpression, ForOfStatement, Function, Statement,
+    VariableDeclaration,
+  },
+  semantic::ScopeFlags,
+};
+use oxc_ast_visit::{Visit, walk};
+
+/// The index of the first top-level statement which suspends the module evaluation,
+/// via `await`, `for await` or `await using`
+pub fn find_top_level_await(body: &[Statement]) -> Option<usize> {
+  body.iter().position(|statement| {
+    let mut finder = TopLevelAwaitFinder { found: false };
+    finder.visit_statement(statement);
+    finder.found
+  })
+}
+
+struct TopLevelAwaitFinder {
+  found: bool,
+}
+
+impl<'a> Visit<'a> for TopLevelAwaitFinder {
+  fn visit_function(&mut self, _it: &Function<'a>, _flags: ScopeFlags) {}
+
+  fn visit_arrow_function_expression(&mut self, _it: &ArrowFunctionExpression<'a>) {}
+
+  fn visit_await_expression(&mut self, _it: &AwaitExpression<'a>) {
+    self.found = true;
+  }
+
+  fn visit_for_of_statement(&mut self, it: &ForOfStatement<'a>) {
+    if it.r#await {
+      self.found = true;
+    } else {
+      walk::walk_for_of_statement(self, it);
+    }
+  }
+
+  fn visit_variable_declaration(&mut self, it: &VariableDeclaration<'a>) {
+    if it.kind.is_await() {
+      self.found = true;
+    } else {
+      walk::walk_variable_declaration(self, it);
+    }
+  }
+}
@@ -4,8 +4,8 @@ use oxc::allocator;
 use rustc_hash::FxHashMap;
 
 use super::{
-  AbstractIterator, ArgumentsValue, EnumeratedProperties, LiteralValue, PropertyKeyValue,
-  TypeofResult, ValueTrait, cacheable::Cacheable, escaped,
+  AbstractIterator, ArgumentsValue, EnumeratedProperties, LiteralValue, LiveIterable,
+  PropertyKeyValue, TypeofResult, ValueTrait, cacheable::Cacheable, escaped,
 };
 use crate::{
   analyzer::{Analyzer, Factory, rw_tracking::ReadWriteTarget},
@@ -336,7 +336,7 @@ impl<'a> ValueTrait<'a> for ArrayValue<'a> {
         analyzer.allocator,
       )),
       analyzer.dep((self.deps(analyzer), dep)),
-      Vec::from_iter([self.array_id()]),
+      vec![LiveIterable::Array(self.array_id())],
     )
   }
 
@@ -0,0 +1,699 @@
+use std::cell::{Cell, RefCell};
+
+use oxc::allocator;
+
+use super::{
+  AbstractIterator, ArgumentsValue, EnumeratedProperties, LiteralValue, LiveIterable,
+  PropertyKeyValue, TypeofResult, ValueTrait, cacheable::Cacheable, escaped, never::NeverValue,
+};
+use crate::{
+  analyzer::{Analyzer, Factory, rw_tracking::ReadWriteTarget},
+  builtin_string,
+  builtins::BuiltinPrototype,
+  define_ptr_idx,
+  dep::{Dep, DepCollector, DepVec},
+  entity::Entity,
+  scope::CfScopeVer,
+  use_included_flag,
+  utils::version::Version,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionKind {
+  Map,
+  Set,
+  WeakMap,
+  WeakSet,
+}
+
+impl CollectionKind {
+  pub fn is_weak(self) -> bool {
+    matches!(self, CollectionKind::WeakMap | CollectionKind::WeakSet)
+  }
+
+  pub fn is_set(self) -> bool {
+    matches!(self, CollectionKind::Set | CollectionKind::WeakSet)
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CollectionEntry<'a> {
+  /// Depends on the last write of the entry
+  pub key: Entity<'a>,
+  /// Same as `key` for sets
+  pub value: Entity<'a>,
+  /// `false` if the entry may be absent
+  pub definite: bool,
+  /// All writes of the entry since it is added, which decide the iteration order
+  pub history: Dep<'a>,
+}
+
+/// A `Map`, `Set`, `WeakMap` or `WeakSet`, whose entries are keyed by literals.
+///
+/// Once an entry is keyed by a non-literal value, the collection is no longer tracked.
+#[derive(Debug)]
+pub struct CollectionValue<'a> {
+  pub kind: CollectionKind,
+  pub included: Cell<bool>,
+  pub version: Version,
+  /// Deletions, and the mutations after the collection is no longer tracked
+  pub deps: RefCell<DepCollector<'a>>,
+  pub cf_scope: CfScopeVer,
+  /// In insertion order, `None` for deleted entries
+  pub entries: RefCell<allocator::Vec<'a, Option<CollectionEntry<'a>>>>,
+  /// Normalized key to the index in `entries`
+  pub index: RefCell<allocator::HashMap<'a, LiteralValue<'a>, usize>>,
+}
+
+define_ptr_idx! {
+  pub struct CollectionId for CollectionValue<'a>;
+}
+
+impl<'a> ValueTrait<'a> for CollectionValue<'a> {
+  fn include(&'a self, analyzer: &mut Analyzer<'a>) {
+    use_included_flag!(self);
+
+    self.version.untrack();
+    self.deps.borrow().include_all(analyzer);
+    for entry in self.entries.borrow().iter().flatten() {
+      analyzer.include((entry.key, entry.value, entry.history));
+    }
+
+    let target_depth = analyzer.find_first_different_cf_scope(self.cf_scope.0);
+    analyzer.track_write(target_depth, self.rw_target(), None);
+    analyzer.request_exhaustive_callbacks(self.rw_target());
+  }
+
+  fn unknown_mutate(&'a self, analyzer: &mut Analyzer<'a>, dep: Dep<'a>) {
+    if self.included.get() {
+      return escaped::unknown_mutate(analyzer, dep);
+    }
+
+    let (is_exhaustive, _, exec_deps) = self.prepare_mutation(analyzer, dep);
+
+    if is_exhaustive {
+      self.include(analyzer);
+      return escaped::unknown_mutate(analyzer, dep);
+    }
+
+    self.version.untrack();
+    self.deps.borrow_mut().push(analyzer.dep(exec_deps));
+  }
+
+  fn get_property(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    key: Entity<'a>,
+  ) -> Entity<'a> {
+    if self.included.get() {
+      return escaped::get_property(self, analyzer, dep, key);
+    }
+
+    let Some(key_literals) = key.get_literals(analyzer) else {
+      return analyzer.factory.computed_unknown((self, dep, key));
+    };
+
+    let dep = analyzer.dep((dep, key));
+    let prototype = self.prototype(analyzer);
+    let mut values = analyzer.factory.vec();
+    for &key_literal in &key_literals {
+      let key = PropertyKeyValue::from(key_literal);
+      if !self.kind.is_weak() && matches!(key, PropertyKeyValue::String(s) if s == "size") {
+        values.push(self.get_size(analyzer));
+      } else if let Some(property) = prototype.get_keyed(analyzer, key, self) {
+        values.push(property);
+      } else {
+        values.push(analyzer.factory.unmatched_prototype_property);
+      }
+    }
+    analyzer.factory.computed_union(values, dep)
+  }
+
+  fn set_property(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    key: Entity<'a>,
+    value: Entity<'a>,
+  ) {
+    self.include(analyzer);
+    escaped::set_property(analyzer, dep, key, value)
+  }
+
+  fn enumerate_properties(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+  ) -> EnumeratedProperties<'a> {
+    if self.included.get() {
+      return escaped::enumerate_properties(self, analyzer, dep);
+    }
+    // Entries are not properties
+    EnumeratedProperties { known: Default::default(), unknown: None, dep }
+  }
+
+  fn delete_property(&'a self, analyzer: &mut Analyzer<'a>, dep: Dep<'a>, key: Entity<'a>) {
+    self.include(analyzer);
+    escaped::delete_property(analyzer, dep, key)
+  }
+
+  fn call(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    this: Entity<'a>,
+    args: ArgumentsValue<'a>,
+  ) -> Entity<'a> {
+    escaped::call(self, analyzer, dep, this, args)
+  }
+
+  fn construct(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    args: ArgumentsValue<'a>,
+  ) -> Entity<'a> {
+    escaped::construct(self, analyzer, dep, args)
+  }
+
+  fn jsx(&'a self, analyzer: &mut Analyzer<'a>, props: Entity<'a>) -> Entity<'a> {
+    escaped::jsx(self, analyzer, props)
+  }
+
+  fn r#await(&'a self, analyzer: &mut Analyzer<'a>, dep: Dep<'a>) -> Entity<'a> {
+    if self.included.get() {
+      return escaped::r#await(analyzer, dep);
+    }
+    analyzer.factory.computed(self.into(), dep)
+  }
+
+  fn iterate(&'a self, analyzer: &mut Analyzer<'a>, dep: Dep<'a>) -> AbstractIterator<'a> {
+    if self.kind.is_weak() {
+      analyzer.throw_builtin_error("Weak collections are not iterable");
+      return if analyzer.config.preserve_exceptions {
+        escaped::iterate(analyzer, dep)
+      } else {
+        NeverValue.iterate(analyzer, dep)
+      };
+    }
+
+    if self.included.get() {
+      return escaped::iterate(analyzer, dep);
+    }
+
+    analyzer.track_read(self.cf_scope.0, self.rw_target(), None);
+
+    if !self.version.trackable() {
+      return (vec![], Some(analyzer.factory.unknown), analyzer.dep((self, dep)), vec![]);
+    }
+
+    let mut elements = vec![];
+    let mut rest = analyzer.factory.vec();
+    let mut histories = analyzer.factory.vec();
+    let entries = self.entries.borrow().iter().flatten().copied().collect::<Vec<_>>();
+    for entry in entries {
+      histories.push(entry.history);
+      let element = if self.kind.is_set() {
+        entry.value
+      } else {
+        let pair = analyzer.new_empty_array();
+        pair.push_element(entry.key);
+        pair.push_element(entry.value);
+        pair.into()
+      };
+      // Entries after a possibly absent one have unknown indices
+      if entry.definite && rest.is_empty() {
+        elements.push(element);
+      } else {
+        rest.push(element);
+      }
+    }
+
+    (
+      elements,
+      analyzer.factory.try_union(rest),
+      analyzer.dep((self.deps(analyzer), histories, dep)),
+      vec![LiveIterable::Collection(self.collection_id())],
+    )
+  }
+
+  fn coerce_string(&'a self, analyzer: &Analyzer<'a>) -> Entity<'a> {
+    if self.included.get() {
+      return escaped::coerce_string(analyzer);
+    }
+    match self.kind {
+      CollectionKind::Map => builtin_string!("[object Map]"),
+      CollectionKind::Set => builtin_string!("[object Set]"),
+      CollectionKind::WeakMap => builtin_string!("[object WeakMap]"),
+      CollectionKind::WeakSet => builtin_string!("[object WeakSet]"),
+    }
+  }
+
+  fn coerce_number(&'a self, analyzer: &Analyzer<'a>) -> Entity<'a> {
+    if self.included.get() {
+      return escaped::coerce_numeric(analyzer);
+    }
+    analyzer.factory.nan
+  }
+
+  fn coerce_primitive(&'a self, analyzer: &Analyzer<'a>) -> Entity<'a> {
+    self.coerce_string(analyzer)
+  }
+
+  fn coerce_property_key(&'a self, analyzer: &Analyzer<'a>) -> Entity<'a> {
+    self.coerce_string(analyzer)
+  }
+
+  fn coerce_jsx_child(&'a self, _analyzer: &Analyzer<'a>) -> Entity<'a> {
+    self.into()
+  }
+
+  fn as_collection(&'a self) -> Option<&'a CollectionValue<'a>> {
+    Some(self)
+  }
+
+  fn test_typeof(&self) -> TypeofResult {
+    TypeofResult::Object
+  }
+
+  fn test_truthy(&self) -> Option<bool> {
+    Some(true)
+  }
+
+  fn test_nullish(&self) -> Option<bool> {
+    Some(false)
+  }
+
+  fn test_has_own(&self, _key: PropertyKeyValue<'a>, check_proto: bool) -> Option<bool> {
+    // Own properties are only added after the collection is included
+    if self.included.get() || check_proto { None } else { Some(false) }
+  }
+
+  fn as_cacheable(&self, _factory: &Factory<'a>) -> Option<Cacheable<'a>> {
+    None
+  }
+}
+
+/// Keys are compared with SameValueZero
+fn normalize_key(key: LiteralValue) -> LiteralValue {
+  match key {
+    LiteralValue::String(s, _) => LiteralValue::String(s, None),
+    LiteralValue::Number(n) if n.0 == 0.0 => LiteralValue::Number(0.0.into()),
+    LiteralValue::Number(n) if n.0.is_nan() => LiteralValue::Number(f64::NAN.into()),
+    _ => key,
+  }
+}
+
+impl<'a> CollectionValue<'a> {
+  pub fn collection_id(&self) -> CollectionId {
+    CollectionId::from_ref(self)
+  }
+
+  fn rw_target(&self) -> ReadWriteTarget<'a> {
+    ReadWriteTarget::Collection(self.collection_id())
+  }
+
+  fn prototype(&self, analyzer: &Analyzer<'a>) -> &'a BuiltinPrototype<'a> {
+    let prototypes = analyzer.builtins.prototypes;
+    match self.kind {
+      CollectionKind::Map => &prototypes.map,
+      CollectionKind::Set => &prototypes.set,
+      CollectionKind::WeakMap => &prototypes.weak_map,
+      CollectionKind::WeakSet => &prototypes.weak_set,
+    }
+  }
+
+  /// Returns `None` if the key is not a literal, or the collection is weak
+  fn get_key_literals(
+    &self,
+    analyzer: &mut Analyzer<'a>,
+    key: Entity<'a>,
+  ) -> Option<Vec<LiteralValue<'a>>> {
+    if self.kind.is_weak() {
+      return None;
+    }
+    let key_literals = key.get_literals(analyzer)?;
+    let mut normalized = vec![];
+    for &key_literal in &key_literals {
+      if let LiteralValue::String(_, Some(atom)) = key_literal {
+        // The key is compared with other values, so it can't be mangled
+        analyzer.include(atom);
+      }
+      normalized.push(normalize_key(key_literal));
+    }
+    Some(normalized)
+  }
+
+  /// Called before reading the entries. Returns `false` if the collection is no longer tracked.
+  fn prepare_read(&self, analyzer: &mut Analyzer<'a>) -> bool {
+    analyzer.track_read(self.cf_scope.0, self.rw_target(), None);
+    self.version.trackable()
+  }
+
+  /// `(is_exhaustive, non_det, exec_deps)`
+  fn prepare_mutation(
+    &self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+  ) -> (bool, bool, DepVec<'a>) {
+    let target_depth = analyzer.find_first_different_cf_scope_for_object(self.cf_scope);
+
+    let mut is_exhaustive = false;
+    let mut non_det = false;
+    let mut exec_deps = analyzer.factory.vec1(dep);
+    for scope in analyzer.scoping.cf.iter_stack_mut().skip(target_depth) {
+      is_exhaustive |= scope.is_exhaustive();
+      non_det |= scope.non_det();
+      if let Some(dep) = scope.deps.collect(analyzer.factory) {
+        exec_deps.push(dep);
+      }
+    }
+
+    analyzer.track_write(target_depth, self.rw_target(), None);
+    analyzer.request_exhaustive_callbacks(self.rw_target());
+
+    (is_exhaustive, non_det, exec_deps)
+  }
+
+  fn deps(&self, analyzer: &Analyzer<'a>) -> Option<Dep<'a>> {
+    self.deps.borrow_mut().collect(analyzer.factory)
+  }
+
+  pub fn untrack(&self, dep: Dep<'a>) {
+    self.version.untrack();
+    self.deps.borrow_mut().push(dep);
+  }
+
+  /// Adds or overwrites the entry, used by `Map::set`, `Set::add` and the constructors
+  pub fn insert(
+    &self,
+    analyzer: &mut Analyzer<'a>,
+    write_dep: Dep<'a>,
+    key: Entity<'a>,
+    value: Entity<'a>,
+    non_det: bool,
+  ) {
+    if self.kind.is_weak()
+      && key.get_literal(analyzer).is_some_and(|key| !matches!(key, LiteralValue::Symbol(_)))
+    {
+      analyzer.throw_builtin_error("Invalid value used in weak collection");
+    }
+
+    if !self.version.increment() {
+      return self.untrack(analyzer.dep((write_dep, key, value)));
+    }
+    let Some(key_literals) = self.get_key_literals(analyzer, key) else {
+      return self.untrack(analyzer.dep((write_dep, key, value)));
+    };
+
+    let definite = !non_det && key_literals.len() == 1;
+    let key = analyzer.factory.computed(key, write_dep);
+    let value = analyzer.factory.computed(value, write_dep);
+    let mut entries = self.entries.borrow_mut();
+    let mut index = self.index.borrow_mut();
+    for key_literal in key_literals {
+      if let Some(&i) = index.get(&key_literal) {
+        let entry = entries[i].as_mut().unwrap();
+        if definite {
+          entry.key = key;
+          entry.value = value;
+        } else {
+          entry.key = analyzer.factory.union((entry.key, key));
+          entry.value = analyzer.factory.union((entry.value, value));
+        }
+        entry.history = analyzer.dep((entry.history, write_dep));
+      } else {
+        index.insert(key_literal, entries.len());
+        entries.push(Some(CollectionEntry { key, value, definite, history: write_dep }));
+      }
+    }
+  }
+
+  pub fn set_entry(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    this: Entity<'a>,
+    args: ArgumentsValue<'a>,
+  ) -> Entity<'a> {
+    let key = args.get(analyzer, 0);
+    let value = if self.kind.is_set() { key } else { args.get(analyzer, 1) };
+
+    let (is_exhaustive, non_det, exec_deps) = self.prepare_mutation(analyzer, dep);
+    if is_exhaustive {
+      self.include(analyzer);
+      return escaped::builtin_call(analyzer, dep, this, args);
+    }
+
+    let write_dep = analyzer.dep(exec_deps);
+    self.insert(analyzer, write_dep, key, value, non_det);
+    analyzer.factory.computed(this, dep)
+  }
+
+  pub fn get_entry(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    key: Entity<'a>,
+  ) -> Entity<'a> {
+    if !self.prepare_read(analyzer) {
+      return analyzer.factory.computed_unknown((self, dep, key));
+    }
+
+    let dep = analyzer.dep((dep, key, self.deps(analyzer)));
+    let key_literals = self.get_key_literals(analyzer, key);
+    let entries = self.entries.borrow();
+    let mut values = analyzer.factory.vec();
+    if let Some(key_literals) = key_literals {
+      let index = self.index.borrow();
+      for key_literal in key_literals {
+        if let Some(&i) = index.get(&key_literal) {
+          let entry = entries[i].unwrap();
+          values.push(entry.value);
+          if !entry.definite {
+            values.push(analyzer.factory.undefined);
+          }
+        } else {
+          values.push(analyzer.factory.undefined);
+        }
+      }
+    } else {
+      values.extend(entries.iter().flatten().map(|entry| entry.value));
+      values.push(analyzer.factory.undefined);
+    }
+    analyzer.factory.computed_union(values, dep)
+  }
+
+  pub fn has_entry(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    key: Entity<'a>,
+  ) -> Entity<'a> {
+    if !self.prepare_read(analyzer) {
+      return analyzer.factory.computed_unknown_boolean((self, dep, key));
+    }
+
+    let dep = analyzer.dep((dep, key, self.deps(analyzer)));
+    let key_literals = self.get_key_literals(analyzer, key);
+    let entries = self.entries.borrow();
+    let mut values = analyzer.factory.vec();
+    if let Some(key_literals) = key_literals {
+      let index = self.index.borrow();
+      for key_literal in key_literals {
+        if let Some(&i) = index.get(&key_literal) {
+          let entry = entries[i].unwrap();
+          values.push(analyzer.factory.computed(
+            if entry.definite { analyzer.factory.r#true } else { analyzer.factory.unknown_boolean },
+            entry.key,
+          ));
+        } else {
+          values.push(analyzer.factory.r#false);
+        }
+      }
+    } else {
+      let keys = allocator::Vec::from_iter_in(
+        entries.iter().flatten().map(|entry| entry.key),
+        analyzer.allocator,
+      );
+      values.push(if keys.is_empty() {
+        analyzer.factory.r#false
+      } else {
+        analyzer.factory.computed_unknown_boolean(keys)
+      });
+    }
+    analyzer.factory.computed_union(values, dep)
+  }
+
+  pub fn delete_entry(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    this: Entity<'a>,
+    args: ArgumentsValue<'a>,
+  ) -> Entity<'a> {
+    let key = args.get(analyzer, 0);
+
+    let (is_exhaustive, non_det, exec_deps) = self.prepare_mutation(analyzer, dep);
+    if is_exhaustive {
+      self.include(analyzer);
+      return escaped::builtin_call(analyzer, dep, this, args);
+    }
+
+    let write_dep = analyzer.dep((exec_deps, key));
+    if !self.version.increment() {
+      self.untrack(write_dep);
+      return analyzer.factory.computed_unknown_boolean(write_dep);
+    }
+
+    let key_literals = self.get_key_literals(analyzer, key);
+    let mut entries = self.entries.borrow_mut();
+    let mut index = self.index.borrow_mut();
+    let Some(key_literals) = key_literals else {
+      for entry in entries.iter_mut().flatten() {
+        entry.definite = false;
+      }
+      self.deps.borrow_mut().push(write_dep);
+      return analyzer.factory.computed_unknown_boolean(write_dep);
+    };
+
+    let definite = !non_det && key_literals.len() == 1;
+    let mut results = analyzer.factory.vec();
+    for key_literal in key_literals {
+      let Some(&i) = index.get(&key_literal) else {
+        results.push(analyzer.factory.r#false);
+        continue;
+      };
+      let entry = entries[i].as_mut().unwrap();
+      results.push(analyzer.factory.computed(
+        if entry.definite { analyzer.factory.r#true } else { analyzer.factory.unknown_boolean },
+        entry.key,
+      ));
+      entry.definite = false;
+      if definite {
+        entries[i] = None;
+        index.remove(&key_literal);
+      }
+      self.deps.borrow_mut().push(write_dep);
+    }
+    analyzer.factory.computed_union(results, (write_dep, self.deps(analyzer)))
+  }
+
+  pub fn clear_entries(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    this: Entity<'a>,
+    args: ArgumentsValue<'a>,
+  ) -> Entity<'a> {
+    let (is_exhaustive, non_det, exec_deps) = self.prepare_mutation(analyzer, dep);
+    if is_exhaustive {
+      self.include(analyzer);
+      return escaped::builtin_call(analyzer, dep, this, args);
+    }
+
+    let write_dep = analyzer.dep(exec_deps);
+    if self.version.increment() {
+      if non_det {
+        for entry in self.entries.borrow_mut().iter_mut().flatten() {
+          entry.definite = false;
+        }
+      } else {
+        self.entries.borrow_mut().clear();
+        self.index.borrow_mut().clear();
+      }
+    }
+    self.deps.borrow_mut().push(write_dep);
+    analyzer.factory.undefined
+  }
+
+  fn get_size(&'a self, analyzer: &mut Analyzer<'a>) -> Entity<'a> {
+    if !self.prepare_read(analyzer) {
+      return analyzer.factory.computed_unknown_number(self);
+    }
+
+    let entries = self.entries.borrow();
+    let keys = allocator::Vec::from_iter_in(
+      entries.iter().flatten().map(|entry| entry.key),
+      analyzer.allocator,
+    );
+    let size = keys.len();
+    let dep = analyzer.dep((keys, self.deps(analyzer)));
+    if entries.iter().flatten().all(|entry| entry.definite) {
+      analyzer.factory.computed(analyzer.factory.number(size as f64), dep)
+    } else {
+      analyzer.factory.computed_unknown_number(dep)
+    }
+  }
+
+  pub fn for_each(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    this: Entity<'a>,
+    args: ArgumentsValue<'a>,
+  ) -> Entity<'a> {
+    if !self.prepare_read(analyzer) {
+      return escaped::builtin_call(analyzer, dep, this, args);
+    }
+
+    let callback = args.get(analyzer, 0);
+    let this_arg = args.get(analyzer, 1);
+    let deps = self.deps(analyzer);
+    let version = self.version.get();
+    let entries = self.entries.borrow().iter().flatten().copied().collect::<Vec<_>>();
+    for entry in entries {
+      // After the collection is mutated by the callback, the rest entries may be deleted
+      let non_det = !entry.definite || self.version.get() != version;
+      if non_det {
+        analyzer.push_non_det_cf_scope();
+      }
+      let args = analyzer.factory.arguments(
+        analyzer.allocator.alloc([
+          entry.value,
+          if self.kind.is_set() { entry.value } else { entry.key },
+          this,
+        ]),
+        None,
+      );
+      callback.call(analyzer, analyzer.dep((dep, deps, entry.history)), this_arg, args);
+      if non_det {
+        analyzer.pop_cf_scope();
+      }
+    }
+
+    if self.version.get() != version {
+      // Entries added by the callback are also visited
+      analyzer.push_non_det_cf_scope();
+      let unknown = analyzer.factory.computed_unknown(self);
+      let args =
+        analyzer.factory.arguments(analyzer.allocator.alloc([unknown, unknown, this]), None);
+      callback.call(analyzer, dep, this_arg, args);
+      analyzer.pop_cf_scope();
+    }
+
+    analyzer.factory.undefined
+  }
+
+  /// `keys`, `values` and `entries` return iterators, which are not tracked
+  pub fn iterator(&'a self, analyzer: &mut Analyzer<'a>, dep: Dep<'a>) -> Entity<'a> {
+    analyzer.track_read(self.cf_scope.0, self.rw_target(), None);
+    analyzer.factory.computed_unknown((self, dep))
+  }
+}
+
+impl<'a> Analyzer<'a> {
+  pub fn new_collection(&mut self, kind: CollectionKind) -> &'a CollectionValue<'a> {
+    let cf_scope = self.current_cf_scope_ver();
+    self.factory.alloc(CollectionValue {
+      kind,
+      included: Cell::new(false),
+      version: Version::default(),
+      deps: RefCell::new(DepCollector::new(self.factory.vec())),
+      cf_scope,
+      entries: RefCell::new(self.factory.vec()),
+      index: RefCell::new(allocator::HashMap::new_in(self.allocator)),
+    })
+  }
+}
@@ -8,6 +8,7 @@ use crate::{
   analyzer::{Analyzer, Factory},
   builtin_string,
   dep::Dep,
+  diagnostic::DiagnosticCode,
   entity::Entity,
   use_included_flag,
   utils::ast::AstKind2,
@@ -25,6 +26,15 @@ trait BuiltinFnImpl<'a>: Debug {
     this: Entity<'a>,
     args: ArgumentsValue<'a>,
   ) -> Entity<'a>;
+  /// Returns `None` if the function is not a modeled constructor
+  fn construct_impl(
+    &self,
+    _analyzer: &mut Analyzer<'a>,
+    _dep: Dep<'a>,
+    _args: ArgumentsValue<'a>,
+  ) -> Option<Entity<'a>> {
+    None
+  }
   fn include(&'a self, _analyzer: &mut Analyzer<'a>) {}
 }
 
@@ -64,6 +74,7 @@ impl<'a, T: BuiltinFnImpl<'a>> ValueTrait<'a> for T {
       object.set_property(analyzer, dep, key, value)
     } else {
       analyzer.add_diagnostic(
+        DiagnosticCode::BuiltinMutation,
         format!(
           "Should not set property of builtin function `{}`, it may cause unexpected tree-shaking behavior",
           self.name()
@@ -77,7 +88,7 @@ impl<'a, T: BuiltinFnImpl<'a>> ValueTrait<'a> for T {
     if let Some(object) = self.statics() {
       object.delete_property(analyzer, dep, key)
     } else {
-      analyzer.add_diagnostic("Should not delete property of builtin function, it may cause unexpected tree-shaking behavior");
+      analyzer.add_diagnostic(DiagnosticCode::BuiltinMutation, "Should not delete property of builtin function, it may cause unexpected tree-shaking behavior");
       escaped::delete_property(analyzer, dep, key)
     }
   }
@@ -108,7 +119,11 @@ impl<'a, T: BuiltinFnImpl<'a>> ValueTrait<'a> for T {
     dep: Dep<'a>,
     args: ArgumentsValue<'a>,
   ) -> Entity<'a> {
-    escaped::construct(self, analyzer, dep, args)
+    if let Some(value) = self.construct_impl(analyzer, dep, args) {
+      value
+    } else {
+      escaped::construct(self, analyzer, dep, args)
+    }
   }
 
   fn jsx(&'a self, analyzer: &mut Analyzer<'a>, props: Entity<'a>) -> Entity<'a> {
@@ -258,6 +273,63 @@ impl<'a> Analyzer<'a> {
   }
 }
 
+pub trait BuiltinConstructorImplementation<'a>:
+  Fn(&mut Analyzer<'a>, Dep<'a>, ArgumentsValue<'a>) -> Entity<'a>
+{
+}
+impl<'a, T: Fn(&mut Analyzer<'a>, Dep<'a>, ArgumentsValue<'a>) -> Entity<'a>>
+  BuiltinConstructorImplementation<'a> for T
+{
+}
+
+/// A builtin class which can only be constructed with `new`
+pub struct ImplementedBuiltinConstructorValue<'a, F: BuiltinConstructorImplementation<'a> + 'a> {
+  pub name: &'static str,
+  pub implementation: F,
+  pub statics: &'a ObjectValue<'a>,
+}
+
+impl<'a, F: BuiltinConstructorImplementation<'a> + 'a> Debug
+  for ImplementedBuiltinConstructorValue<'a, F>
+{
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("ImplementedBuiltinConstructorValue").finish()
+  }
+}
+
+impl<'a, F: BuiltinConstructorImplementation<'a> + 'a> BuiltinFnImpl<'a>
+  for ImplementedBuiltinConstructorValue<'a, F>
+{
+  fn name(&self) -> &'static str {
+    self.name
+  }
+  fn statics(&self) -> Option<&'a ObjectValue<'a>> {
+    Some(self.statics)
+  }
+  fn call_impl(
+    &self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    this: Entity<'a>,
+    args: ArgumentsValue<'a>,
+  ) -> Entity<'a> {
+    analyzer.throw_builtin_error(format!("Constructor {} requires 'new'", self.name));
+    if analyzer.config.preserve_exceptions {
+      escaped::builtin_call(analyzer, dep, this, args)
+    } else {
+      analyzer.factory.never
+    }
+  }
+  fn construct_impl(
+    &self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    args: ArgumentsValue<'a>,
+  ) -> Option<Entity<'a>> {
+    Some((self.implementation)(analyzer, dep, args))
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct PureBuiltinFnValue<'a> {
   name: &'static str,
@@ -319,4 +391,13 @@ impl<'a> crate::analyzer::Factory<'a> {
       })
       .into()
   }
+
+  pub fn implemented_builtin_constructor<F: BuiltinConstructorImplementation<'a> + 'a>(
+    &self,
+    name: &'static str,
+    implementation: F,
+    statics: &'a ObjectValue<'a>,
+  ) -> Entity<'a> {
+    self.alloc(ImplementedBuiltinConstructorValue { name, implementation, statics }).into()
+  }
 }
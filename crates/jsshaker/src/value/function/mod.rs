@@ -17,6 +17,7 @@ use crate::{
   analyzer::{Analyzer, Factory},
   builtin_string,
   dep::{Dep, DepAtom},
+  diagnostic::DiagnosticCode,
   entity::Entity,
   scope::VariableScopeId,
   utils::{CalleeInfo, CalleeNode, ast::AstKind2},
@@ -228,13 +229,17 @@ impl<'a> FunctionValue<'a> {
     self.statics.get_property(analyzer, self, dep, builtin_string!("prototype"))
   }
 
-  fn check_recursion(&self, analyzer: &Analyzer<'a>) -> bool {
+  fn check_recursion(&self, analyzer: &mut Analyzer<'a>) -> bool {
     if !self.finite_recursion {
       let mut recursion_depth = 0usize;
       for scope in analyzer.scoping.call.iter().rev() {
         if scope.callee.instance_id == self.callee.instance_id {
           recursion_depth += 1;
           if recursion_depth >= analyzer.config.max_recursion_depth {
+            analyzer.add_diagnostic(
+              DiagnosticCode::RecursionLimit,
+              "Recursion depth limit is reached, the function body is included as a whole",
+            );
             return true;
           }
         }
@@ -0,0 +1,398 @@
+use std::cell::{Cell, RefCell};
+
+use oxc::{allocator, ast::ast::PropertyKind};
+
+use super::{
+  AbstractIterator, ArgumentsValue, EnumeratedProperties, ObjectPrototype, PropertyKeyValue,
+  TypeofResult, ValueTrait, cacheable::Cacheable, escaped,
+};
+use crate::{
+  analyzer::{Analyzer, Factory},
+  builtin_string,
+  dep::{Dep, DepCollector, LazyDep},
+  entity::Entity,
+  scope::CfScopeVer,
+  use_included_flag,
+};
+
+/// The value of `cursor` after the generator is closed
+const CLOSED: usize = usize::MAX;
+
+/// A generator object, whose body is executed when the generator function is called.
+///
+/// The yielded values are recorded in order, until the control flow becomes non-deterministic.
+#[derive(Debug)]
+pub struct GeneratorValue<'a> {
+  pub included: Cell<bool>,
+  pub cf_scope: CfScopeVer,
+  /// Values yielded at known indices
+  pub yielded: RefCell<allocator::Vec<'a, Entity<'a>>>,
+  /// Values yielded at unknown indices
+  pub rest: RefCell<allocator::Vec<'a, Entity<'a>>>,
+  /// The completion value of the body, `None` before the body is executed
+  pub returned: Cell<Option<Entity<'a>>>,
+  /// The execution of the yields, which decides the number of yielded values
+  pub deps: RefCell<DepCollector<'a>>,
+  /// The calls which resume the body, needed once the body has side effects
+  pub consumers: LazyDep<'a, Dep<'a>>,
+  /// The number of consumed values, `None` if unknown
+  cursor: Cell<Option<usize>>,
+}
+
+impl<'a> ValueTrait<'a> for GeneratorValue<'a> {
+  fn include(&'a self, analyzer: &mut Analyzer<'a>) {
+    use_included_flag!(self);
+    analyzer.include(self.returned.get());
+    for &value in self.yielded.borrow().iter().chain(self.rest.borrow().iter()) {
+      analyzer.include(value);
+    }
+    self.deps.borrow().include_all(analyzer);
+    analyzer.include(self.consumers);
+  }
+
+  fn unknown_mutate(&'a self, analyzer: &mut Analyzer<'a>, dep: Dep<'a>) {
+    self.include(analyzer);
+    escaped::unknown_mutate(analyzer, dep)
+  }
+
+  fn get_property(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    key: Entity<'a>,
+  ) -> Entity<'a> {
+    analyzer.builtins.prototypes.generator.get_property(analyzer, self.into(), key, dep)
+  }
+
+  fn set_property(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    key: Entity<'a>,
+    value: Entity<'a>,
+  ) {
+    self.include(analyzer);
+    escaped::set_property(analyzer, dep, key, value)
+  }
+
+  fn enumerate_properties(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+  ) -> EnumeratedProperties<'a> {
+    escaped::enumerate_properties(self, analyzer, dep)
+  }
+
+  fn delete_property(&'a self, analyzer: &mut Analyzer<'a>, dep: Dep<'a>, key: Entity<'a>) {
+    self.include(analyzer);
+    escaped::delete_property(analyzer, dep, key)
+  }
+
+  fn call(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    this: Entity<'a>,
+    args: ArgumentsValue<'a>,
+  ) -> Entity<'a> {
+    escaped::call(self, analyzer, dep, this, args)
+  }
+
+  fn construct(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    args: ArgumentsValue<'a>,
+  ) -> Entity<'a> {
+    escaped::construct(self, analyzer, dep, args)
+  }
+
+  fn jsx(&'a self, analyzer: &mut Analyzer<'a>, props: Entity<'a>) -> Entity<'a> {
+    escaped::jsx(self, analyzer, props)
+  }
+
+  fn r#await(&'a self, analyzer: &mut Analyzer<'a>, dep: Dep<'a>) -> Entity<'a> {
+    if self.included.get() {
+      return escaped::r#await(analyzer, dep);
+    }
+    analyzer.factory.computed(self.into(), dep)
+  }
+
+  fn iterate(&'a self, analyzer: &mut Analyzer<'a>, dep: Dep<'a>) -> AbstractIterator<'a> {
+    let Some((cursor, non_det)) = self.prepare_consume(analyzer, dep) else {
+      return escaped::iterate(analyzer, dep);
+    };
+    // The iteration either exhausts the generator, or closes it via `return()`
+    self.advance(non_det, CLOSED);
+
+    let (elements, rest) = match cursor {
+      Some(CLOSED) => (vec![], None),
+      Some(cursor) => {
+        let yielded = self.yielded.borrow();
+        let rest = self.rest.borrow();
+        (
+          yielded.iter().skip(cursor).copied().collect(),
+          analyzer
+            .factory
+            .try_union(allocator::Vec::from_iter_in(rest.iter().copied(), analyzer.allocator)),
+        )
+      }
+      None => (vec![], self.all_yielded(analyzer)),
+    };
+
+    (elements, rest, analyzer.dep((self.deps(analyzer), dep)), vec![])
+  }
+
+  fn coerce_string(&'a self, analyzer: &Analyzer<'a>) -> Entity<'a> {
+    analyzer.factory.computed_unknown_string(self)
+  }
+
+  fn coerce_number(&'a self, analyzer: &Analyzer<'a>) -> Entity<'a> {
+    analyzer.factory.computed_unknown(self)
+  }
+
+  fn coerce_primitive(&'a self, analyzer: &Analyzer<'a>) -> Entity<'a> {
+    analyzer.factory.computed_unknown_primitive(self)
+  }
+
+  fn coerce_property_key(&'a self, analyzer: &Analyzer<'a>) -> Entity<'a> {
+    self.coerce_string(analyzer)
+  }
+
+  fn coerce_jsx_child(&'a self, _analyzer: &Analyzer<'a>) -> Entity<'a> {
+    self.into()
+  }
+
+  fn as_generator(&'a self) -> Option<&'a GeneratorValue<'a>> {
+    Some(self)
+  }
+
+  fn test_typeof(&self) -> TypeofResult {
+    TypeofResult::Object
+  }
+
+  fn test_truthy(&self) -> Option<bool> {
+    Some(true)
+  }
+
+  fn test_nullish(&self) -> Option<bool> {
+    Some(false)
+  }
+
+  fn test_has_own(&self, _key: PropertyKeyValue<'a>, _check_proto: bool) -> Option<bool> {
+    None
+  }
+
+  fn as_cacheable(&self, _factory: &Factory<'a>) -> Option<Cacheable<'a>> {
+    None
+  }
+}
+
+impl<'a> GeneratorValue<'a> {
+  pub fn deps(&self, analyzer: &Analyzer<'a>) -> Option<Dep<'a>> {
+    self.deps.borrow_mut().collect(analyzer.factory)
+  }
+
+  /// Called by the `yield` expressions in the body
+  pub fn add_yielded(
+    &self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    elements: &[Entity<'a>],
+    rest: Option<Entity<'a>>,
+  ) {
+    let fn_depth = analyzer.call_scope().cf_scope_depth;
+    let (exec_deps, non_det) = analyzer.get_exec_dep(fn_depth);
+    let dep = analyzer.dep((dep, exec_deps));
+
+    if self.included.get() || self.returned.get().is_some() {
+      // Re-executed because the outer states are changed
+      analyzer.include((dep, rest));
+      for &element in elements {
+        analyzer.include(element);
+      }
+      return;
+    }
+
+    self.deps.borrow_mut().push(dep);
+    let mut yielded = self.yielded.borrow_mut();
+    let mut rest_values = self.rest.borrow_mut();
+    for &element in elements {
+      let element = analyzer.factory.computed(element, dep);
+      // Values after a possibly skipped one have unknown indices
+      if non_det || !rest_values.is_empty() {
+        rest_values.push(element);
+      } else {
+        yielded.push(element);
+      }
+    }
+    if let Some(rest) = rest {
+      rest_values.push(analyzer.factory.computed(rest, dep));
+    }
+  }
+
+  /// Called after the body is executed for the first time
+  fn finish(&self, analyzer: &mut Analyzer<'a>, returned: Result<Entity<'a>, Entity<'a>>) {
+    match returned {
+      Ok(returned) => self.returned.set(Some(returned)),
+      Err(returned) => {
+        // The body is actually executed later, when the read states may be changed
+        let mut values = analyzer.factory.vec1(returned);
+        values.extend(self.yielded.borrow_mut().drain(..));
+        values.extend(self.rest.borrow_mut().drain(..));
+        let unknown = analyzer.factory.computed_unknown(values);
+        self.rest.borrow_mut().push(unknown);
+        self.returned.set(Some(unknown));
+      }
+    }
+  }
+
+  /// Returns `None` if the generator is no longer tracked, otherwise the cursor before the
+  /// consumption, and whether the consumption is non-deterministic
+  fn prepare_consume(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+  ) -> Option<(Option<usize>, bool)> {
+    if self.included.get() {
+      return None;
+    }
+
+    let target_depth = analyzer.find_first_different_cf_scope_for_object(self.cf_scope);
+    let mut is_exhaustive = false;
+    let mut non_det = false;
+    for scope in analyzer.scoping.cf.iter_stack().skip(target_depth) {
+      is_exhaustive |= scope.is_exhaustive();
+      non_det |= scope.non_det();
+    }
+
+    if is_exhaustive {
+      self.include(analyzer);
+      return None;
+    }
+
+    self.consumers.push(analyzer, dep);
+    Some((self.cursor.get(), non_det))
+  }
+
+  fn advance(&self, non_det: bool, cursor: usize) {
+    self.cursor.set(if non_det { None } else { Some(cursor) });
+  }
+
+  /// All the values which may be yielded, in unknown order
+  fn all_yielded(&self, analyzer: &Analyzer<'a>) -> Option<Entity<'a>> {
+    let yielded = self.yielded.borrow();
+    let rest = self.rest.borrow();
+    analyzer.factory.try_union(allocator::Vec::from_iter_in(
+      yielded.iter().chain(rest.iter()).copied(),
+      analyzer.allocator,
+    ))
+  }
+
+  fn iter_result(
+    &self,
+    analyzer: &mut Analyzer<'a>,
+    value: Entity<'a>,
+    done: Entity<'a>,
+  ) -> Entity<'a> {
+    let object = analyzer
+      .new_empty_object(ObjectPrototype::Builtin(&analyzer.builtins.prototypes.object), None);
+    object.init_property(analyzer, PropertyKind::Init, builtin_string!("value"), value, true);
+    object.init_property(analyzer, PropertyKind::Init, builtin_string!("done"), done, true);
+    object.into()
+  }
+
+  /// `Generator.prototype.next`
+  pub fn next(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    args: ArgumentsValue<'a>,
+  ) -> Entity<'a> {
+    // The sent value becomes the value of a `yield` expression, which is unknown
+    let dep = analyzer.dep((dep, args));
+    let Some((cursor, non_det)) = self.prepare_consume(analyzer, dep) else {
+      analyzer.include(dep);
+      return analyzer.factory.unknown;
+    };
+
+    let factory = analyzer.factory;
+    let yielded_len = self.yielded.borrow().len();
+    let has_rest = !self.rest.borrow().is_empty();
+    let returned = self.returned.get().unwrap_or(factory.unknown);
+    let (value, done) = match cursor {
+      Some(CLOSED) => (factory.undefined, factory.r#true),
+      Some(cursor) if cursor < yielded_len => {
+        self.advance(non_det, cursor + 1);
+        (self.yielded.borrow()[cursor], factory.r#false)
+      }
+      Some(_) if !has_rest => {
+        self.advance(non_det, CLOSED);
+        (returned, factory.r#true)
+      }
+      _ => {
+        self.cursor.set(None);
+        let mut values = factory.vec();
+        values.extend(self.all_yielded(analyzer));
+        values.push(returned);
+        values.push(factory.undefined);
+        (factory.union(values), factory.unknown_boolean)
+      }
+    };
+
+    let result = self.iter_result(analyzer, value, done);
+    factory.computed(result, (dep, self.deps(analyzer)))
+  }
+
+  /// `Generator.prototype.return`
+  pub fn r#return(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    value: Entity<'a>,
+  ) -> Entity<'a> {
+    let Some((_, non_det)) = self.prepare_consume(analyzer, dep) else {
+      analyzer.include((dep, value));
+      return analyzer.factory.unknown;
+    };
+    // `finally` blocks in the body may override the result, which are ignored here
+    self.advance(non_det, CLOSED);
+    let result = self.iter_result(analyzer, value, analyzer.factory.r#true);
+    analyzer.factory.computed(result, dep)
+  }
+}
+
+impl<'a> Analyzer<'a> {
+  pub fn new_generator(&mut self) -> &'a GeneratorValue<'a> {
+    let cf_scope = self.current_cf_scope_ver();
+    self.factory.alloc(GeneratorValue {
+      included: Cell::new(false),
+      cf_scope,
+      yielded: RefCell::new(self.factory.vec()),
+      rest: RefCell::new(self.factory.vec()),
+      returned: Cell::new(None),
+      deps: RefCell::new(DepCollector::new(self.factory.vec())),
+      consumers: self.factory.lazy_dep(self.factory.vec()),
+      cursor: Cell::new(Some(0)),
+    })
+  }
+
+  /// Executes the body of a generator function, which is resumed by the returned generator
+  pub fn exec_generator_body(
+    &mut self,
+    runner: impl Fn(&mut Analyzer<'a>, &'a GeneratorValue<'a>) -> Entity<'a> + 'a,
+  ) -> &'a GeneratorValue<'a> {
+    let generator = self.new_generator();
+    let returned = self.exec_async_callback(move |analyzer| {
+      analyzer.push_dependent_cf_scope(generator.consumers);
+      let returned = runner(analyzer, generator);
+      analyzer.pop_cf_scope();
+      if generator.returned.get().is_some() {
+        analyzer.include(returned);
+      }
+      returned
+    });
+    generator.finish(self, returned);
+    generator
+  }
+}
@@ -38,6 +38,9 @@ impl<'a> SymbolRegistry<'a> {
   }
 }
 
+/// `Symbol.toStringTag`, see `init_symbol_constructor`
+pub const SYMBOL_TO_STRING_TAG: SymbolId = SymbolId::from_usize(11);
+
 #[macro_export]
 macro_rules! builtin_symbol {
   ($n:literal) => {{
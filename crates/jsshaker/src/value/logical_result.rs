@@ -6,7 +6,7 @@ use crate::{
   analyzer::{Analyzer, Factory},
   dep::Dep,
   entity::Entity,
-  value::ObjectValue,
+  value::{ObjectValue, generator::GeneratorValue, promise::PromiseValue},
 };
 
 #[derive(Debug, Clone)]
@@ -144,6 +144,14 @@ impl<'a> ValueTrait<'a> for LogicalResultValue<'a> {
     self.value.as_object()
   }
 
+  fn as_promise(&'a self) -> Option<&'a PromiseValue<'a>> {
+    self.value.as_promise()
+  }
+
+  fn as_generator(&'a self) -> Option<&'a GeneratorValue<'a>> {
+    self.value.as_generator()
+  }
+
   fn test_typeof(&self) -> TypeofResult {
     self.value.test_typeof()
   }
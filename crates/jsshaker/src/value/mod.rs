@@ -1,13 +1,16 @@
 pub mod array;
 pub mod cacheable;
+pub mod collection;
 pub mod escaped;
 mod function;
+pub mod generator;
 pub mod literal;
 pub mod logical_result;
 pub mod module_object;
 pub mod never;
 mod object;
 pub mod primitive;
+pub mod promise;
 pub mod react_element;
 mod typeof_result;
 pub mod union;
@@ -15,10 +18,13 @@ pub mod unknown;
 pub mod utils;
 
 use cacheable::Cacheable;
+use collection::CollectionValue;
 pub use function::*;
+use generator::GeneratorValue;
 pub use literal::LiteralValue;
 pub use object::*;
 use oxc::{semantic::SymbolId, span::Atom};
+use promise::PromiseValue;
 use rustc_hash::FxHashMap;
 use std::{cmp::Ordering, fmt::Debug};
 pub use typeof_result::TypeofResult;
@@ -27,7 +33,7 @@ use crate::{
   analyzer::{Analyzer, Factory},
   dep::{CustomDepTrait, Dep},
   entity::Entity,
-  value::{array::ArrayId, literal::PossibleLiterals},
+  value::{array::ArrayId, collection::CollectionId, literal::PossibleLiterals},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -62,7 +68,30 @@ impl<'a> EnumeratedProperties<'a> {
 
 /// (vec![known_elements], rest, dep)
 pub type IteratedElements<'a> = (Vec<Entity<'a>>, Option<Entity<'a>>, Dep<'a>);
-pub type AbstractIterator<'a> = (Vec<Entity<'a>>, Option<Entity<'a>>, Dep<'a>, Vec<ArrayId>);
+pub type AbstractIterator<'a> = (Vec<Entity<'a>>, Option<Entity<'a>>, Dep<'a>, Vec<LiveIterable>);
+
+/// An iterated value whose mutations during the iteration are visible to the iterator
+#[derive(Debug, Clone, Copy)]
+pub enum LiveIterable {
+  Array(ArrayId),
+  Collection(CollectionId),
+}
+
+impl LiveIterable {
+  pub fn version(self) -> usize {
+    match self {
+      LiveIterable::Array(id) => id.as_ref().version.get(),
+      LiveIterable::Collection(id) => id.as_ref().version.get(),
+    }
+  }
+
+  pub fn as_value<'a>(self) -> Value<'a> {
+    match self {
+      LiveIterable::Array(id) => id.as_ref(),
+      LiveIterable::Collection(id) => id.as_ref(),
+    }
+  }
+}
 
 pub enum UnionHint {
   Unknown,
@@ -152,6 +181,15 @@ pub trait ValueTrait<'a>: Debug {
   fn as_object(&'a self) -> Option<&'a ObjectValue<'a>> {
     None
   }
+  fn as_collection(&'a self) -> Option<&'a CollectionValue<'a>> {
+    None
+  }
+  fn as_promise(&'a self) -> Option<&'a PromiseValue<'a>> {
+    None
+  }
+  fn as_generator(&'a self) -> Option<&'a GeneratorValue<'a>> {
+    None
+  }
 
   fn test_typeof(&self) -> TypeofResult;
   fn test_truthy(&self) -> Option<bool>;
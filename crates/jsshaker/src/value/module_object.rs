@@ -1,15 +1,19 @@
 use std::cell::Cell;
 
+use oxc::semantic::SymbolId;
+use rustc_hash::FxHashMap;
+
 use crate::{
   Analyzer,
   analyzer::Factory,
+  builtin_string,
   dep::Dep,
   entity::Entity,
   module::ModuleId,
   use_included_flag,
   value::{
-    AbstractIterator, ArgumentsValue, EnumeratedProperties, LiteralValue, TypeofResult, ValueTrait,
-    cacheable::Cacheable, escaped,
+    AbstractIterator, ArgumentsValue, EnumeratedProperties, LiteralValue, PropertyKeyValue,
+    TypeofResult, ValueTrait, cacheable::Cacheable, escaped, literal::symbol::SYMBOL_TO_STRING_TAG,
   },
 };
 
@@ -53,7 +57,10 @@ impl<'a> ValueTrait<'a> for ModuleObjectValue {
               result.push(analyzer.factory.undefined);
             }
           }
-          LiteralValue::Symbol(_key) => todo!(),
+          // The prototype is `null`
+          LiteralValue::Symbol(key) => {
+            result.push(symbol_keyed_property(key).unwrap_or(analyzer.factory.undefined))
+          }
           _ => unreachable!("Invalid property key"),
         }
       }
@@ -65,16 +72,34 @@ impl<'a> ValueTrait<'a> for ModuleObjectValue {
 
   fn set_property(
     &'a self,
-    _analyzer: &mut Analyzer<'a>,
-    _dep: Dep<'a>,
-    _key: Entity<'a>,
-    _value: Entity<'a>,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    key: Entity<'a>,
+    value: Entity<'a>,
   ) {
-    // Do nothing
-  }
-
-  fn delete_property(&'a self, _analyzer: &mut Analyzer<'a>, _dep: Dep<'a>, _key: Entity<'a>) {
-    // Do nothing
+    // Namespace objects are immutable, the assignment always fails
+    self.fail_in_strict_mode(analyzer, "Cannot assign to property of module namespace object");
+    analyzer.include((dep, key, value));
+  }
+
+  fn delete_property(&'a self, analyzer: &mut Analyzer<'a>, dep: Dep<'a>, key: Entity<'a>) {
+    let Some(key_literals) = key.get_literals(analyzer) else {
+      analyzer.global_effect();
+      analyzer.include((dep, key));
+      return;
+    };
+    let may_fail = (&key_literals).into_iter().any(|&key_literal| match key_literal {
+      LiteralValue::String(key, _) => {
+        analyzer.may_module_export(self.module, key, &mut Default::default())
+      }
+      LiteralValue::Symbol(key) => symbol_keyed_property(key).is_some(),
+      _ => unreachable!("Invalid property key"),
+    });
+    if may_fail {
+      // Exports and `Symbol.toStringTag` are non-configurable
+      self.fail_in_strict_mode(analyzer, "Cannot delete property of module namespace object");
+      analyzer.include((dep, key));
+    }
   }
 
   fn enumerate_properties(
@@ -82,8 +107,20 @@ impl<'a> ValueTrait<'a> for ModuleObjectValue {
     analyzer: &mut Analyzer<'a>,
     dep: Dep<'a>,
   ) -> EnumeratedProperties<'a> {
-    // TODO: Optimize this
-    escaped::enumerate_properties(self, analyzer, dep)
+    let Some(keys) = self.get_keys(analyzer, false) else {
+      return escaped::enumerate_properties(self, analyzer, dep);
+    };
+    let mut known = FxHashMap::default();
+    for (_, key) in keys {
+      let Some(LiteralValue::String(name, _)) = key.get_literal(analyzer) else {
+        unreachable!("Exported names are strings");
+      };
+      let value = analyzer
+        .get_export_value_by_name(self.module, *name, &mut Default::default())
+        .unwrap_or(analyzer.factory.unknown);
+      known.insert(PropertyKeyValue::String(name), (true, key, value));
+    }
+    EnumeratedProperties { known, unknown: None, dep }
   }
 
   fn call(
@@ -176,12 +213,12 @@ impl<'a> ValueTrait<'a> for ModuleObjectValue {
     Some(false)
   }
 
-  fn test_has_own(
-    &self,
-    _key: crate::value::PropertyKeyValue<'a>,
-    _check_proto: bool,
-  ) -> Option<bool> {
-    None
+  fn test_has_own(&self, key: PropertyKeyValue<'a>, _check_proto: bool) -> Option<bool> {
+    match key {
+      PropertyKeyValue::String(_) => None,
+      // The prototype is `null`
+      PropertyKeyValue::Symbol(key) => Some(symbol_keyed_property(key).is_some()),
+    }
   }
 
   fn as_cacheable(&self, _factory: &Factory<'a>) -> Option<Cacheable<'a>> {
@@ -189,8 +226,21 @@ impl<'a> ValueTrait<'a> for ModuleObjectValue {
   }
 }
 
+/// The only property keyed by a symbol is `Symbol.toStringTag`, which is the literal `"Module"`
+fn symbol_keyed_property<'a>(key: SymbolId) -> Option<Entity<'a>> {
+  (key == SYMBOL_TO_STRING_TAG).then(|| builtin_string!("Module"))
+}
+
 impl ModuleObjectValue {
   pub fn new(module: ModuleId) -> Self {
     Self { included: Cell::new(false), module }
   }
+
+  /// Silently fails in sloppy mode, e.g. in scripts which load the module via `import()`
+  fn fail_in_strict_mode(&self, analyzer: &mut Analyzer<'_>, message: &str) {
+    let module = analyzer.module_info();
+    if !module.is_commonjs && !module.is_script {
+      analyzer.throw_builtin_error(message);
+    }
+  }
 }
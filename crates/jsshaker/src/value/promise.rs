@@ -0,0 +1,458 @@
+use std::{
+  cell::{Cell, RefCell},
+  ptr,
+};
+
+use super::{
+  AbstractIterator, ArgumentsValue, EnumeratedProperties, PropertyKeyValue, TypeofResult,
+  ValueTrait, cacheable::Cacheable, escaped,
+};
+use crate::{
+  analyzer::{Analyzer, Factory},
+  builtin_string,
+  dep::{Dep, DepCollector},
+  entity::Entity,
+  use_included_flag,
+};
+
+/// A promise whose possible settlements are known.
+///
+/// If neither `fulfilled` nor `rejected` is set, the promise is never settled.
+#[derive(Debug)]
+pub struct PromiseValue<'a> {
+  pub included: Cell<bool>,
+  /// The value it may be fulfilled with, which is never a thenable
+  pub fulfilled: Cell<Option<Entity<'a>>>,
+  /// The reason it may be rejected with
+  pub rejected: Cell<Option<Entity<'a>>>,
+  /// The calls which settle the promise
+  pub deps: RefCell<DepCollector<'a>>,
+  /// The depth of the executor's control flow scope, `Some` while the executor is running
+  executor_depth: Cell<Option<usize>>,
+  /// The later calls of the resolving functions are ignored once it is definitely settled
+  settled: Cell<bool>,
+}
+
+impl<'a> ValueTrait<'a> for PromiseValue<'a> {
+  fn include(&'a self, analyzer: &mut Analyzer<'a>) {
+    use_included_flag!(self);
+    analyzer.include((self.fulfilled.get(), self.rejected.get()));
+    self.deps.borrow().include_all(analyzer);
+  }
+
+  fn unknown_mutate(&'a self, analyzer: &mut Analyzer<'a>, dep: Dep<'a>) {
+    self.include(analyzer);
+    escaped::unknown_mutate(analyzer, dep)
+  }
+
+  fn get_property(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    key: Entity<'a>,
+  ) -> Entity<'a> {
+    analyzer.builtins.prototypes.promise.get_property(analyzer, self.into(), key, dep)
+  }
+
+  fn set_property(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    key: Entity<'a>,
+    value: Entity<'a>,
+  ) {
+    self.include(analyzer);
+    escaped::set_property(analyzer, dep, key, value)
+  }
+
+  fn enumerate_properties(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+  ) -> EnumeratedProperties<'a> {
+    escaped::enumerate_properties(self, analyzer, dep)
+  }
+
+  fn delete_property(&'a self, analyzer: &mut Analyzer<'a>, dep: Dep<'a>, key: Entity<'a>) {
+    self.include(analyzer);
+    escaped::delete_property(analyzer, dep, key)
+  }
+
+  fn call(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    this: Entity<'a>,
+    args: ArgumentsValue<'a>,
+  ) -> Entity<'a> {
+    escaped::call(self, analyzer, dep, this, args)
+  }
+
+  fn construct(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    args: ArgumentsValue<'a>,
+  ) -> Entity<'a> {
+    escaped::construct(self, analyzer, dep, args)
+  }
+
+  fn jsx(&'a self, analyzer: &mut Analyzer<'a>, props: Entity<'a>) -> Entity<'a> {
+    escaped::jsx(self, analyzer, props)
+  }
+
+  fn r#await(&'a self, analyzer: &mut Analyzer<'a>, dep: Dep<'a>) -> Entity<'a> {
+    if self.included.get() {
+      return escaped::r#await(analyzer, dep);
+    }
+    match (self.fulfilled.get(), self.rejected.get()) {
+      (Some(value), None) => analyzer.factory.computed(value, dep),
+      (value, Some(reason)) => {
+        // The `await` may throw, so the settling calls are needed even if the value is unused
+        analyzer.include((dep, reason, self.deps(analyzer)));
+        if let Some(value) = value {
+          value
+        } else {
+          analyzer.exit_by_throw(true);
+          analyzer.factory.never
+        }
+      }
+      (None, None) => escaped::r#await(analyzer, dep),
+    }
+  }
+
+  fn iterate(&'a self, analyzer: &mut Analyzer<'a>, dep: Dep<'a>) -> AbstractIterator<'a> {
+    self.include(analyzer);
+    escaped::iterate(analyzer, dep)
+  }
+
+  fn coerce_string(&'a self, analyzer: &Analyzer<'a>) -> Entity<'a> {
+    analyzer.factory.computed_unknown_string(self)
+  }
+
+  fn coerce_number(&'a self, analyzer: &Analyzer<'a>) -> Entity<'a> {
+    analyzer.factory.computed_unknown(self)
+  }
+
+  fn coerce_primitive(&'a self, analyzer: &Analyzer<'a>) -> Entity<'a> {
+    analyzer.factory.computed_unknown_primitive(self)
+  }
+
+  fn coerce_property_key(&'a self, analyzer: &Analyzer<'a>) -> Entity<'a> {
+    self.coerce_string(analyzer)
+  }
+
+  fn coerce_jsx_child(&'a self, _analyzer: &Analyzer<'a>) -> Entity<'a> {
+    self.into()
+  }
+
+  fn as_promise(&'a self) -> Option<&'a PromiseValue<'a>> {
+    Some(self)
+  }
+
+  fn test_typeof(&self) -> TypeofResult {
+    TypeofResult::Object
+  }
+
+  fn test_truthy(&self) -> Option<bool> {
+    Some(true)
+  }
+
+  fn test_nullish(&self) -> Option<bool> {
+    Some(false)
+  }
+
+  fn test_has_own(&self, _key: PropertyKeyValue<'a>, _check_proto: bool) -> Option<bool> {
+    None
+  }
+
+  fn as_cacheable(&self, _factory: &Factory<'a>) -> Option<Cacheable<'a>> {
+    None
+  }
+}
+
+impl<'a> PromiseValue<'a> {
+  pub fn deps(&self, analyzer: &Analyzer<'a>) -> Option<Dep<'a>> {
+    self.deps.borrow_mut().collect(analyzer.factory)
+  }
+
+  pub fn add_fulfilled(&self, analyzer: &Analyzer<'a>, value: Entity<'a>) {
+    self.fulfilled.set(Some(match self.fulfilled.get() {
+      Some(old) => analyzer.factory.union((old, value)),
+      None => value,
+    }));
+  }
+
+  pub fn add_rejected(&self, analyzer: &Analyzer<'a>, reason: Entity<'a>) {
+    self.rejected.set(Some(match self.rejected.get() {
+      Some(old) => analyzer.factory.union((old, reason)),
+      None => reason,
+    }));
+  }
+
+  /// May be settled with anything, e.g. by a foreign thenable
+  pub fn add_unknown(&self, analyzer: &Analyzer<'a>, dep: Dep<'a>) {
+    let unknown = analyzer.factory.computed_unknown(dep);
+    self.add_fulfilled(analyzer, unknown);
+    self.add_rejected(analyzer, unknown);
+  }
+
+  /// The promise resolve functions, which adopt the state of a thenable `value`
+  pub fn resolve(&'a self, analyzer: &mut Analyzer<'a>, dep: Dep<'a>, value: Entity<'a>) {
+    if let Some(promise) = value.as_promise() {
+      if ptr::eq(promise, self) {
+        // TypeError: Chaining cycle detected for promise
+        self.add_rejected(analyzer, analyzer.factory.computed_unknown(dep));
+        return;
+      }
+      let dep =
+        analyzer.dep((dep, value.get_shallow_dep(analyzer.factory), promise.deps(analyzer)));
+      if let Some(value) = promise.fulfilled.get() {
+        self.add_fulfilled(analyzer, analyzer.factory.computed(value, dep));
+      }
+      if let Some(reason) = promise.rejected.get() {
+        self.add_rejected(analyzer, analyzer.factory.computed(reason, dep));
+      }
+    } else if value.test_typeof().intersects(TypeofResult::Object | TypeofResult::Function) {
+      let then = value.get_property(analyzer, dep, builtin_string!("then"));
+      if then.test_is_undefined() == Some(true) {
+        self.add_fulfilled(analyzer, analyzer.factory.computed(value, then));
+      } else {
+        // `then` is called later with unknown resolving functions
+        analyzer.include((value, then));
+        self.add_unknown(analyzer, dep);
+      }
+    } else {
+      self.add_fulfilled(analyzer, analyzer.factory.computed(value, dep));
+    }
+  }
+
+  /// Called by the resolving functions passed to the executor
+  fn settle_by_executor(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    value: Entity<'a>,
+    reject: bool,
+  ) {
+    if self.settled.get() {
+      return;
+    }
+    let Some(executor_depth) = self.executor_depth.get() else {
+      // The promise is already treated as settled with unknown values
+      analyzer.include((dep, value));
+      return;
+    };
+
+    let (exec_deps, non_det) = analyzer.get_exec_dep(executor_depth);
+    let dep = analyzer.dep((dep, exec_deps));
+    if reject {
+      self.add_rejected(analyzer, analyzer.factory.computed(value, dep));
+    } else {
+      self.resolve(analyzer, dep, value);
+    }
+    self.deps.borrow_mut().push(dep);
+    if !non_det {
+      self.settled.set(true);
+    }
+  }
+
+  /// Calls `callback` after the promise is settled. Returns the promise resolved with its result.
+  fn call_later(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    callback: Entity<'a>,
+    arg: Option<Entity<'a>>,
+    result: &'a PromiseValue<'a>,
+  ) {
+    let settle_deps = self.deps(analyzer);
+    let throw_dep: &Cell<Option<Dep<'a>>> = analyzer.allocator.alloc(Cell::new(None));
+    let first_round: &Cell<bool> = analyzer.allocator.alloc(Cell::new(true));
+    let returned = analyzer.exec_async_callback(move |analyzer| {
+      let old_try_catch_depth =
+        analyzer.scoping.try_catch_depth.replace(analyzer.scoping.cf.current_depth());
+      analyzer.push_dependent_cf_scope((dep, settle_deps));
+      let args = match arg {
+        Some(arg) => analyzer.factory.arguments(analyzer.allocator.alloc([arg]), None),
+        None => analyzer.factory.empty_arguments,
+      };
+      let ret_val = callback.call(analyzer, dep, analyzer.factory.undefined, args);
+      let mut cf_scope = analyzer.pop_cf_scope();
+      if cf_scope.exited != Some(false) {
+        // The rejection depends on the code which throws
+        let dep = analyzer.dep((throw_dep.get(), cf_scope.deps.collect(analyzer.factory)));
+        throw_dep.set(Some(dep));
+      }
+      analyzer.scoping.try_catch_depth = old_try_catch_depth;
+      if !first_round.replace(false) {
+        // Re-running means the result is already unknown
+        analyzer.include(ret_val);
+      }
+      ret_val
+    });
+
+    match returned {
+      Ok(returned) => {
+        result.resolve(analyzer, dep, returned);
+        if let Some(throw_dep) = throw_dep.get() {
+          result.add_rejected(analyzer, analyzer.factory.computed_unknown(throw_dep));
+        }
+      }
+      Err(returned) => result.add_unknown(analyzer, analyzer.dep((dep, returned))),
+    }
+  }
+
+  /// `Promise.prototype.then`
+  pub fn then(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    on_fulfilled: Entity<'a>,
+    on_rejected: Entity<'a>,
+  ) -> Entity<'a> {
+    let result = analyzer.new_promise();
+    let settle_deps = self.deps(analyzer);
+    let pass_dep = analyzer.dep((dep, settle_deps));
+
+    for (state, callback, is_rejection) in
+      [(self.fulfilled.get(), on_fulfilled, false), (self.rejected.get(), on_rejected, true)]
+    {
+      let Some(state) = state else {
+        continue;
+      };
+      let callback_typeof = callback.test_typeof();
+      if callback_typeof.contains(TypeofResult::Function) {
+        self.call_later(analyzer, dep, callback, Some(state), result);
+      }
+      if callback_typeof != TypeofResult::Function {
+        // Non-callable callbacks pass the state through
+        let state = analyzer.factory.computed(state, (pass_dep, callback));
+        if is_rejection {
+          result.add_rejected(analyzer, state);
+        } else {
+          result.add_fulfilled(analyzer, state);
+        }
+      }
+    }
+
+    analyzer.factory.computed(result.into(), dep)
+  }
+
+  /// `Promise.prototype.finally`
+  pub fn finally(
+    &'a self,
+    analyzer: &mut Analyzer<'a>,
+    dep: Dep<'a>,
+    on_finally: Entity<'a>,
+  ) -> Entity<'a> {
+    let fulfilled = self.fulfilled.get();
+    let rejected = self.rejected.get();
+    if fulfilled.is_none() && rejected.is_none() {
+      return analyzer.factory.computed(self.into(), dep);
+    }
+
+    let result = analyzer.new_promise();
+    if on_finally.test_typeof().contains(TypeofResult::Function) {
+      // The value returned by the callback is ignored, unless it is a rejected promise
+      let returned = analyzer.new_promise();
+      self.call_later(analyzer, dep, on_finally, None, returned);
+      if let Some(reason) = returned.rejected.get() {
+        result.add_rejected(analyzer, reason);
+      }
+    }
+    let pass_dep = analyzer.dep((dep, self.deps(analyzer)));
+    if let Some(value) = fulfilled {
+      result.add_fulfilled(analyzer, analyzer.factory.computed(value, pass_dep));
+    }
+    if let Some(reason) = rejected {
+      result.add_rejected(analyzer, analyzer.factory.computed(reason, pass_dep));
+    }
+
+    analyzer.factory.computed(result.into(), dep)
+  }
+}
+
+impl<'a> Analyzer<'a> {
+  /// A promise which is never settled, until `resolve` or `reject` is called on it
+  pub fn new_promise(&mut self) -> &'a PromiseValue<'a> {
+    self.factory.alloc(PromiseValue {
+      included: Cell::new(false),
+      fulfilled: Cell::new(None),
+      rejected: Cell::new(None),
+      deps: RefCell::new(DepCollector::new(self.factory.vec())),
+      executor_depth: Cell::new(None),
+      settled: Cell::new(true),
+    })
+  }
+
+  /// `new Promise(executor)`
+  pub fn construct_promise(&mut self, dep: Dep<'a>, executor: Entity<'a>) -> Entity<'a> {
+    if !executor.test_typeof().contains(TypeofResult::Function) {
+      self.throw_builtin_error("Promise resolver is not a function");
+      if self.config.preserve_exceptions {
+        self.include((dep, executor));
+        return self.factory.unknown;
+      }
+      return self.factory.never;
+    }
+
+    let promise = self.new_promise();
+    promise.settled.set(false);
+
+    let resolve =
+      self.dynamic_implemented_builtin("Promise::resolve", move |analyzer, dep, _, args| {
+        let value = args.get(analyzer, 0);
+        promise.settle_by_executor(analyzer, dep, value, false);
+        analyzer.factory.undefined
+      });
+    let reject =
+      self.dynamic_implemented_builtin("Promise::reject", move |analyzer, dep, _, args| {
+        let reason = args.get(analyzer, 0);
+        promise.settle_by_executor(analyzer, dep, reason, true);
+        analyzer.factory.undefined
+      });
+
+    // Exceptions thrown by the executor reject the promise
+    self.push_dependent_cf_scope(dep);
+    let old_try_catch_depth = self.scoping.try_catch_depth.replace(self.scoping.cf.current_depth());
+    self.push_non_det_cf_scope();
+    promise.executor_depth.set(Some(self.scoping.cf.stack_len()));
+    let args = self.factory.arguments(self.allocator.alloc([resolve, reject]), None);
+    executor.call(self, dep, self.factory.undefined, args);
+    promise.executor_depth.set(None);
+    self.pop_cf_scope();
+    self.scoping.try_catch_depth = old_try_catch_depth;
+    let mut cf_scope = self.pop_cf_scope();
+
+    if cf_scope.exited != Some(false) {
+      // The rejection depends on the code which throws
+      let throw_dep = cf_scope.deps.collect(self.factory);
+      promise.add_rejected(self, self.factory.computed_unknown(throw_dep));
+    }
+    if !promise.settled.get() {
+      // The resolving functions may be called later
+      promise.add_unknown(self, dep);
+    }
+
+    self.factory.computed(promise.into(), dep)
+  }
+
+  /// A new promise resolved with `value`
+  pub fn resolved_promise(&mut self, dep: Dep<'a>, value: Entity<'a>) -> &'a PromiseValue<'a> {
+    let promise = self.new_promise();
+    promise.resolve(self, dep, value);
+    promise.deps.borrow_mut().push(dep);
+    promise
+  }
+
+  /// `Promise.resolve(value)`, which returns `value` itself if it is a promise
+  pub fn promise_resolve(&mut self, dep: Dep<'a>, value: Entity<'a>) -> Entity<'a> {
+    if value.as_promise().is_some() {
+      self.factory.computed(value, dep)
+    } else {
+      let promise = self.resolved_promise(dep, value);
+      self.factory.computed(promise.into(), dep)
+    }
+  }
+}
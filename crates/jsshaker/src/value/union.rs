@@ -11,6 +11,7 @@ use crate::{
   analyzer::{Analyzer, Factory},
   dep::Dep,
   entity::Entity,
+  module::ModuleId,
   use_included_flag,
   utils::skip_hash_eq::SkipHashEq,
   value::{ObjectId, ObjectValue, array::ArrayId, literal::PossibleLiterals},
@@ -186,7 +187,7 @@ impl<'a, V: UnionValues<'a> + Debug + 'a> ValueTrait<'a> for UnionValue<'a, V> {
     let mut max_elements = usize::MAX;
     let mut rest = analyzer.factory.vec();
     let mut deps = analyzer.factory.vec();
-    let mut array_ids = Vec::new();
+    let mut live_iterables = Vec::new();
 
     analyzer.push_non_det_cf_scope();
     for entity in self.values.iter() {
@@ -206,7 +207,7 @@ impl<'a, V: UnionValues<'a> + Debug + 'a> ValueTrait<'a> for UnionValue<'a, V> {
         rest.push(el);
       }
       deps.push(d);
-      array_ids.extend(arr);
+      live_iterables.extend(arr);
     }
     if elements.len() > max_elements {
       for e in elements.drain(max_elements..) {
@@ -227,7 +228,7 @@ impl<'a, V: UnionValues<'a> + Debug + 'a> ValueTrait<'a> for UnionValue<'a, V> {
         .collect(),
       analyzer.factory.try_union(rest),
       analyzer.factory.dep(deps),
-      array_ids,
+      live_iterables,
     )
   }
 
@@ -424,6 +425,7 @@ pub struct UnionMerger<'a> {
   unknown: Option<Entity<'a>>,
   objects: FxHashMap<ObjectId, Entity<'a>>,
   arrays: FxHashMap<ArrayId, Entity<'a>>,
+  module_objects: FxHashMap<ModuleId, Entity<'a>>,
   strings: Vec<Entity<'a>>,
   others: FxHashMap<Cacheable<'a>, Entity<'a>>,
 }
@@ -460,6 +462,13 @@ impl<'a> UnionMerger<'a> {
           .and_modify(|v| *v = factory.computed(*v, value.get_shallow_dep(factory)))
           .or_insert(value);
       }
+      Cacheable::ModuleObject(module_id) => {
+        self
+          .module_objects
+          .entry(module_id)
+          .and_modify(|v| *v = factory.computed(*v, value.get_shallow_dep(factory)))
+          .or_insert(value);
+      }
       Cacheable::String(_) => {
         self.strings.push(value);
       }
@@ -490,7 +499,7 @@ impl<'a> UnionMerger<'a> {
     results.extend(merger.others.values().copied());
 
     if let Some(unknown) = merger.unknown {
-      if merger.objects.is_empty() && merger.arrays.is_empty() {
+      if merger.objects.is_empty() && merger.arrays.is_empty() && merger.module_objects.is_empty() {
         return if results.is_empty() { unknown } else { factory.computed(unknown, results) };
       } else {
         results.push(unknown);
@@ -499,6 +508,7 @@ impl<'a> UnionMerger<'a> {
 
     results.extend(merger.objects.values().copied());
     results.extend(merger.arrays.values().copied());
+    results.extend(merger.module_objects.values().copied());
 
     match results.len() {
       0 => factory.never,
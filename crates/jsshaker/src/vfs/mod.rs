@@ -0,0 +1,315 @@
+mod node_resolver;
+
+use std::{
+  collections::HashMap,
+  io::{self, ErrorKind},
+  rc::Rc,
+};
+
+pub use node_resolver::NodeResolverFs;
+use oxc::span::SourceType;
+
+/// Extensions tried in order when the specifier doesn't resolve to an existing file.
+const RESOLVE_EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "ts", "tsx", "mts", "cts"];
+
+pub trait Vfs {
+  /// Returns `Ok(None)` if the module is external, e.g. a bare specifier that is not handled.
+  /// Returns an error if the module should exist but can't be resolved.
+  fn resolve_module(&self, importer: &str, specifier: &str) -> io::Result<Option<String>>;
+  fn read_file(&self, path: &str) -> io::Result<String>;
+
+  /// The source type of the module. `None` means JavaScript, with JSX and module kind from config.
+  ///
+  /// By default, TypeScript is detected from the file extension.
+  fn source_type(&self, path: &str) -> Option<SourceType> {
+    SourceType::from_path(path).ok().filter(|ty| ty.is_typescript()).map(|ty| ty.with_module(true))
+  }
+
+  /// Whether importing the module may have side effects.
+  ///
+  /// Imports of side-effect-free modules are removed when none of their bindings are used.
+  /// Returns an error if it can't be determined, e.g. `sideEffects` of package.json is invalid,
+  /// and the module is assumed to have side effects.
+  fn has_side_effects(&self, _path: &str) -> io::Result<bool> {
+    Ok(true)
+  }
+
+  /// Names of the files directly in the directory, used to expand `import(`./dir/${name}.js`)`.
+  fn read_dir(&self, _path: &str) -> io::Result<Vec<String>> {
+    Ok(vec![])
+  }
+
+  /// The source map of the module's source text, e.g. when the module is the output of a bundler.
+  ///
+  /// The output source map is composed with it, so it maps to the original sources.
+  fn read_source_map(&self, _path: &str) -> Option<String> {
+    None
+  }
+}
+
+/// Allows the Vfs to be shared, e.g. kept by a session between runs
+impl<T: Vfs + ?Sized> Vfs for Rc<T> {
+  fn resolve_module(&self, importer: &str, specifier: &str) -> io::Result<Option<String>> {
+    (**self).resolve_module(importer, specifier)
+  }
+
+  fn read_file(&self, path: &str) -> io::Result<String> {
+    (**self).read_file(path)
+  }
+
+  fn source_type(&self, path: &str) -> Option<SourceType> {
+    (**self).source_type(path)
+  }
+
+  fn has_side_effects(&self, path: &str) -> io::Result<bool> {
+    (**self).has_side_effects(path)
+  }
+
+  fn read_dir(&self, path: &str) -> io::Result<Vec<String>> {
+    (**self).read_dir(path)
+  }
+
+  fn read_source_map(&self, path: &str) -> Option<String> {
+    (**self).read_source_map(path)
+  }
+}
+
+fn read_std_dir(path: &str) -> io::Result<Vec<String>> {
+  let mut names = vec![];
+  for entry in std::fs::read_dir(path)? {
+    let entry = entry?;
+    if !entry.file_type()?.is_dir() {
+      names.push(entry.file_name().to_string_lossy().into_owned());
+    }
+  }
+  Ok(names)
+}
+
+/// Reads the `.map` file next to the module
+fn read_std_source_map(path: &str) -> Option<String> {
+  std::fs::read_to_string(format!("{path}.map")).ok()
+}
+
+fn not_found(specifier: &str, importer: &str) -> io::Error {
+  io::Error::new(ErrorKind::NotFound, format!("Cannot resolve module {specifier} from {importer}"))
+}
+
+pub struct StdFs;
+
+impl Vfs for StdFs {
+  fn resolve_module(&self, importer: &str, specifier: &str) -> io::Result<Option<String>> {
+    let is_abs = specifier.starts_with("/");
+    if !is_abs && !specifier.starts_with(".") {
+      return Ok(None);
+    }
+
+    let mut path = if is_abs {
+      std::env::current_dir()?.join(specifier.strip_prefix("/").unwrap())
+    } else {
+      let mut path = std::path::PathBuf::from(importer);
+      path.pop();
+      path.push(specifier);
+      path
+    };
+    path = normalize_path::normalize(&path);
+    let result = path.exists().then(|| path.to_string_lossy().into_owned()).or_else(|| {
+      RESOLVE_EXTENSIONS.iter().find_map(|ext| {
+        path.set_extension(ext);
+        path.exists().then(|| path.to_string_lossy().into_owned())
+      })
+    });
+
+    result.map(Some).ok_or_else(|| not_found(specifier, importer))
+  }
+
+  fn read_file(&self, path: &str) -> io::Result<String> {
+    std::fs::read_to_string(path)
+  }
+
+  fn read_dir(&self, path: &str) -> io::Result<Vec<String>> {
+    read_std_dir(path)
+  }
+
+  fn read_source_map(&self, path: &str) -> Option<String> {
+    read_std_source_map(path)
+  }
+}
+
+pub struct SingleFileFs(pub String);
+
+impl SingleFileFs {
+  pub const ENTRY_PATH: &'static str = "/entry.js";
+}
+
+impl Vfs for SingleFileFs {
+  fn resolve_module(&self, _importer: &str, _specifier: &str) -> io::Result<Option<String>> {
+    Ok(None)
+  }
+
+  fn read_file(&self, path: &str) -> io::Result<String> {
+    if path == Self::ENTRY_PATH {
+      Ok(self.0.clone())
+    } else {
+      Err(io::Error::new(ErrorKind::NotFound, format!("Unexpected path: {path}")))
+    }
+  }
+}
+
+pub struct MultiModuleFs {
+  pub sources: HashMap<String, String>,
+  /// Source maps of the sources, keyed by the same paths
+  pub source_maps: HashMap<String, String>,
+}
+
+impl MultiModuleFs {
+  pub fn new(sources: HashMap<String, String>) -> Self {
+    Self { sources, source_maps: HashMap::new() }
+  }
+
+  pub fn with_source_maps(mut self, source_maps: HashMap<String, String>) -> Self {
+    self.source_maps = source_maps;
+    self
+  }
+
+  fn exists(&self, path: &std::path::Path) -> bool {
+    self.sources.contains_key(path.to_str().unwrap())
+  }
+}
+
+impl Vfs for MultiModuleFs {
+  fn resolve_module(&self, importer: &str, specifier: &str) -> io::Result<Option<String>> {
+    if !specifier.starts_with(".") {
+      return Ok(None);
+    }
+
+    let mut path = std::path::PathBuf::from(importer);
+    path.pop();
+    path.push(specifier);
+    path = normalize_path::normalize(&path);
+    self
+      .exists(&path)
+      .then(|| path.to_string_lossy().into_owned())
+      .or_else(|| {
+        RESOLVE_EXTENSIONS.iter().find_map(|ext| {
+          path.set_extension(ext);
+          self.exists(&path).then(|| path.to_string_lossy().into_owned())
+        })
+      })
+      .map(Some)
+      .ok_or_else(|| not_found(specifier, importer))
+  }
+
+  fn read_file(&self, path: &str) -> io::Result<String> {
+    self
+      .sources
+      .get(path)
+      .cloned()
+      .ok_or_else(|| io::Error::new(ErrorKind::NotFound, format!("File not found: {path}")))
+  }
+
+  fn read_dir(&self, path: &str) -> io::Result<Vec<String>> {
+    let dir = std::path::Path::new(path);
+    Ok(
+      self
+        .sources
+        .keys()
+        .filter_map(|file| {
+          let file = std::path::Path::new(file);
+          if file.parent() != Some(dir) {
+            return None;
+          }
+          file.file_name()?.to_str().map(String::from)
+        })
+        .collect(),
+    )
+  }
+
+  fn read_source_map(&self, path: &str) -> Option<String> {
+    self.source_maps.get(path).cloned()
+  }
+}
+
+// Credit: https://github.com/rust-lang/rfcs/issues/2208#issuecomment-342679694
+pub mod normalize_path {
+  use std::path::Component;
+  use std::path::Path;
+  use std::path::PathBuf;
+
+  pub fn normalize_str(p: &str) -> String {
+    normalize(&PathBuf::from(p)).to_string_lossy().into_owned()
+  }
+
+  pub fn normalize(p: &Path) -> PathBuf {
+    let mut stack: Vec<Component> = vec![];
+
+    // We assume .components() removes redundant consecutive path separators.
+    // Note that .components() also does some normalization of '.' on its own anyways.
+    // This '.' normalization happens to be compatible with the approach below.
+    for component in p.components() {
+      match component {
+        // Drop CurDir components, do not even push onto the stack.
+        Component::CurDir => {}
+
+        // For ParentDir components, we need to use the contents of the stack.
+        Component::ParentDir => {
+          // Look at the top element of stack, if any.
+          let top = stack.last().cloned();
+
+          match top {
+            // A component is on the stack, need more pattern matching.
+            Some(c) => {
+              match c {
+                // Push the ParentDir on the stack.
+                Component::Prefix(_) => {
+                  stack.push(component);
+                }
+
+                // The parent of a RootDir is itself, so drop the ParentDir (no-op).
+                Component::RootDir => {}
+
+                // A CurDir should never be found on the stack, since they are dropped when seen.
+                Component::CurDir => {
+                  unreachable!();
+                }
+
+                // If a ParentDir is found, it must be due to it piling up at the start of a path.
+                // Push the new ParentDir onto the stack.
+                Component::ParentDir => {
+                  stack.push(component);
+                }
+
+                // If a Normal is found, pop it off.
+                Component::Normal(_) => {
+                  let _ = stack.pop();
+                }
+              }
+            }
+
+            // Stack is empty, so path is empty, just push.
+            None => {
+              stack.push(component);
+            }
+          }
+        }
+
+        // All others, simply push onto the stack.
+        _ => {
+          stack.push(component);
+        }
+      }
+    }
+
+    // If an empty PathBuf would be return, instead return CurDir ('.').
+    if stack.is_empty() {
+      return PathBuf::from(&Component::CurDir);
+    }
+
+    let mut norm_path = PathBuf::new();
+
+    for item in &stack {
+      norm_path.push(item);
+    }
+
+    norm_path
+  }
+}
@@ -0,0 +1,398 @@
+use std::{
+  cell::RefCell,
+  io::{self, ErrorKind},
+  path::{Path, PathBuf},
+  rc::Rc,
+};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use rustc_hash::FxHashMap;
+use serde_json::{Map, Value};
+
+use super::{
+  RESOLVE_EXTENSIONS, Vfs, normalize_path, not_found, read_std_dir, read_std_source_map,
+};
+
+/// Invalid package.json files are cached as the error messages
+type CachedPackageJson = Result<Option<Rc<Value>>, String>;
+
+/// The compiled `sideEffects` field of a package.json
+enum SideEffects {
+  All,
+  None,
+  /// Only the matching files have side effects
+  Patterns(GlobSet),
+}
+
+/// Invalid patterns are cached as the error messages
+type CachedSideEffects = Result<Rc<SideEffects>, String>;
+
+fn compile_side_effects(package_dir: &Path, package_json: &Value) -> CachedSideEffects {
+  match package_json.get("sideEffects") {
+    Some(Value::Bool(false)) => Ok(SideEffects::None),
+    Some(Value::Array(patterns)) => {
+      let mut builder = GlobSetBuilder::new();
+      for original in patterns.iter().filter_map(Value::as_str) {
+        let pattern = original.strip_prefix("./").unwrap_or(original);
+        // Like webpack, patterns without a slash match files in any directory
+        let pattern =
+          if pattern.contains('/') { pattern.to_string() } else { format!("**/{pattern}") };
+        let glob = GlobBuilder::new(&pattern).literal_separator(true).build().map_err(|error| {
+          format!(
+            "Invalid sideEffects pattern {original:?} in {}: {error}",
+            package_dir.join("package.json").display()
+          )
+        })?;
+        builder.add(glob);
+      }
+      builder.build().map(SideEffects::Patterns).map_err(|error| error.to_string())
+    }
+    _ => Ok(SideEffects::All),
+  }
+  .map(Rc::new)
+}
+
+/// Resolves modules like Node.js does, including packages in `node_modules`.
+pub struct NodeResolverFs {
+  /// Conditions to match in `exports`/`imports` of package.json. `default` always matches.
+  /// When the importer is CommonJS, `require` matches instead of `import`.
+  pub conditions: Vec<String>,
+  /// Fields of package.json to use as the entry when there is no `exports`
+  pub main_fields: Vec<String>,
+  package_json_cache: RefCell<FxHashMap<PathBuf, CachedPackageJson>>,
+  /// Keyed by the package directories
+  side_effects_cache: RefCell<FxHashMap<PathBuf, CachedSideEffects>>,
+}
+
+impl Default for NodeResolverFs {
+  fn default() -> Self {
+    Self::new(
+      ["import", "module", "browser", "production"].map(String::from).to_vec(),
+      ["module", "main"].map(String::from).to_vec(),
+    )
+  }
+}
+
+impl NodeResolverFs {
+  pub fn new(conditions: Vec<String>, main_fields: Vec<String>) -> Self {
+    Self {
+      conditions,
+      main_fields,
+      package_json_cache: Default::default(),
+      side_effects_cache: Default::default(),
+    }
+  }
+
+  /// Reads `dir/package.json`, returns `None` if it doesn't exist, or an error if it is invalid
+  pub fn read_package_json(&self, dir: &Path) -> io::Result<Option<Rc<Value>>> {
+    self
+      .package_json_cache
+      .borrow_mut()
+      .entry(dir.to_path_buf())
+      .or_insert_with(|| {
+        let Ok(content) = std::fs::read_to_string(dir.join("package.json")) else {
+          return Ok(None);
+        };
+        serde_json::from_str(&content)
+          .map(|value| Some(Rc::new(value)))
+          .map_err(|error| format!("Invalid package.json in {}: {error}", dir.display()))
+      })
+      .clone()
+      .map_err(|message| io::Error::new(ErrorKind::InvalidData, message))
+  }
+
+  /// Finds the nearest package.json from `dir` upwards
+  pub fn find_package_json(&self, dir: &Path) -> io::Result<Option<(PathBuf, Rc<Value>)>> {
+    for dir in dir.ancestors() {
+      if let Some(package_json) = self.read_package_json(dir)? {
+        return Ok(Some((dir.to_path_buf(), package_json)));
+      }
+    }
+    Ok(None)
+  }
+
+  /// Checks the `sideEffects` field of the nearest package.json
+  fn has_side_effects_in_package(&self, path: &Path) -> io::Result<bool> {
+    // Invalid package.json files are reported when resolving the module
+    let Some(Ok(Some((package_dir, package_json)))) =
+      path.parent().map(|dir| self.find_package_json(dir))
+    else {
+      return Ok(true);
+    };
+    let side_effects = self
+      .side_effects_cache
+      .borrow_mut()
+      .entry(package_dir.clone())
+      .or_insert_with(|| compile_side_effects(&package_dir, &package_json))
+      .clone()
+      .map_err(|message| io::Error::new(ErrorKind::InvalidData, message))?;
+    Ok(match &*side_effects {
+      SideEffects::All => true,
+      SideEffects::None => false,
+      SideEffects::Patterns(set) => {
+        path.strip_prefix(&package_dir).is_ok_and(|relative| set.is_match(relative))
+      }
+    })
+  }
+
+  fn resolve_path(&self, path: &Path) -> io::Result<Option<PathBuf>> {
+    match self.resolve_file(path) {
+      Some(resolved) => Ok(Some(resolved)),
+      None => self.resolve_directory(path),
+    }
+  }
+
+  fn resolve_file(&self, path: &Path) -> Option<PathBuf> {
+    if path.is_file() {
+      return Some(path.to_path_buf());
+    }
+    RESOLVE_EXTENSIONS.iter().find_map(|ext| {
+      let mut path = path.as_os_str().to_owned();
+      path.push(".");
+      path.push(ext);
+      let path = PathBuf::from(path);
+      path.is_file().then_some(path)
+    })
+  }
+
+  fn resolve_directory(&self, dir: &Path) -> io::Result<Option<PathBuf>> {
+    if let Some(package_json) = self.read_package_json(dir)? {
+      for field in &self.main_fields {
+        if let Some(main) = package_json.get(field).and_then(Value::as_str)
+          && let Some(resolved) = self.resolve_path(&normalize_path::normalize(&dir.join(main)))?
+        {
+          return Ok(Some(resolved));
+        }
+      }
+    }
+    Ok(self.resolve_file(&dir.join("index")))
+  }
+
+  /// `.cjs` files, and `.js` files in packages with `"type": "commonjs"`. Files without an
+  /// explicit type are treated as ES modules, as bundlers do.
+  fn is_commonjs(&self, path: &Path) -> io::Result<bool> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+      Some("cjs" | "cts") => Ok(true),
+      Some("js" | "jsx" | "ts" | "tsx") => {
+        let Some(dir) = path.parent() else { return Ok(false) };
+        Ok(self.find_package_json(dir)?.is_some_and(|(_, package_json)| {
+          package_json.get("type").and_then(Value::as_str) == Some("commonjs")
+        }))
+      }
+      _ => Ok(false),
+    }
+  }
+
+  fn matches_condition(&self, condition: &str, is_commonjs: bool) -> bool {
+    match condition {
+      "default" => true,
+      "require" => is_commonjs,
+      "import" if is_commonjs => false,
+      _ => self.conditions.iter().any(|c| c == condition),
+    }
+  }
+
+  fn resolve_bare(
+    &self,
+    dir: &Path,
+    specifier: &str,
+    is_commonjs: bool,
+  ) -> io::Result<Option<PathBuf>> {
+    // `@scope/name/subpath` or `name/subpath`
+    let name_len = if specifier.starts_with('@') {
+      specifier.match_indices('/').nth(1).map_or(specifier.len(), |(index, _)| index)
+    } else {
+      specifier.find('/').unwrap_or(specifier.len())
+    };
+    let (name, subpath) = specifier.split_at(name_len);
+
+    for dir in dir.ancestors() {
+      let package_dir = dir.join("node_modules").join(name);
+      if package_dir.is_dir() {
+        // Like Node.js, the parent directories are not searched once the package is found
+        return self.resolve_package(&package_dir, subpath, is_commonjs).map(Some);
+      }
+    }
+    Ok(None)
+  }
+
+  fn resolve_package(
+    &self,
+    package_dir: &Path,
+    subpath: &str,
+    is_commonjs: bool,
+  ) -> io::Result<PathBuf> {
+    let package_json = self.read_package_json(package_dir)?;
+    if let Some(exports) = package_json.as_ref().and_then(|p| p.get("exports")) {
+      // Files not listed in `exports` are not accessible
+      return self
+        .resolve_exports(package_dir, exports, &format!(".{subpath}"), is_commonjs)?
+        .ok_or_else(|| {
+          io::Error::new(
+            ErrorKind::NotFound,
+            format!("Package subpath .{subpath} is not exported from {}", package_dir.display()),
+          )
+        });
+    }
+    match subpath.strip_prefix('/') {
+      Some(subpath) => self.resolve_path(&package_dir.join(subpath))?,
+      None => self.resolve_directory(package_dir)?,
+    }
+    .ok_or_else(|| {
+      io::Error::new(
+        ErrorKind::NotFound,
+        format!("Cannot resolve .{subpath} in package {}", package_dir.display()),
+      )
+    })
+  }
+
+  fn resolve_exports(
+    &self,
+    package_dir: &Path,
+    exports: &Value,
+    subpath: &str,
+    is_commonjs: bool,
+  ) -> io::Result<Option<PathBuf>> {
+    match exports {
+      Value::Object(map) if map.keys().next().is_some_and(|key| key.starts_with('.')) => {
+        self.resolve_subpath_map(package_dir, map, subpath, false, is_commonjs)
+      }
+      // Sugar for `{ ".": exports }`
+      _ if subpath == "." => self.resolve_target(package_dir, exports, None, false, is_commonjs),
+      _ => Ok(None),
+    }
+  }
+
+  fn resolve_package_imports(
+    &self,
+    dir: &Path,
+    specifier: &str,
+    is_commonjs: bool,
+  ) -> io::Result<Option<PathBuf>> {
+    let Some((package_dir, package_json)) = self.find_package_json(dir)? else {
+      return Ok(None);
+    };
+    let Some(imports) = package_json.get("imports").and_then(Value::as_object) else {
+      return Ok(None);
+    };
+    self.resolve_subpath_map(&package_dir, imports, specifier, true, is_commonjs)
+  }
+
+  fn resolve_subpath_map(
+    &self,
+    package_dir: &Path,
+    map: &Map<String, Value>,
+    key: &str,
+    is_imports: bool,
+    is_commonjs: bool,
+  ) -> io::Result<Option<PathBuf>> {
+    if let Some(target) = map.get(key)
+      && !key.contains('*')
+    {
+      return self.resolve_target(package_dir, target, None, is_imports, is_commonjs);
+    }
+
+    // Patterns like `./features/*.js`, the one with the longest prefix wins
+    let Some((_, target, matched)) = map
+      .iter()
+      .filter_map(|(pattern, target)| {
+        let (prefix, suffix) = pattern.split_once('*')?;
+        let matched = key.strip_prefix(prefix)?.strip_suffix(suffix)?;
+        Some((prefix.len(), target, matched))
+      })
+      .max_by_key(|(prefix_len, _, _)| *prefix_len)
+    else {
+      return Ok(None);
+    };
+    self.resolve_target(package_dir, target, Some(matched), is_imports, is_commonjs)
+  }
+
+  fn resolve_target(
+    &self,
+    package_dir: &Path,
+    target: &Value,
+    matched: Option<&str>,
+    is_imports: bool,
+    is_commonjs: bool,
+  ) -> io::Result<Option<PathBuf>> {
+    match target {
+      Value::String(target) => {
+        let target = match matched {
+          Some(matched) => target.replace('*', matched),
+          None => target.clone(),
+        };
+        if let Some(relative) = target.strip_prefix("./") {
+          let path = normalize_path::normalize(&package_dir.join(relative));
+          Ok(path.is_file().then_some(path))
+        } else if is_imports {
+          // `imports` may map to another package
+          self.resolve_bare(package_dir, &target, is_commonjs)
+        } else {
+          Ok(None)
+        }
+      }
+      Value::Array(targets) => {
+        for target in targets {
+          if let Some(resolved) =
+            self.resolve_target(package_dir, target, matched, is_imports, is_commonjs)?
+          {
+            return Ok(Some(resolved));
+          }
+        }
+        Ok(None)
+      }
+      Value::Object(conditions) => {
+        for (condition, target) in conditions {
+          if self.matches_condition(condition, is_commonjs)
+            && let Some(resolved) =
+              self.resolve_target(package_dir, target, matched, is_imports, is_commonjs)?
+          {
+            return Ok(Some(resolved));
+          }
+        }
+        Ok(None)
+      }
+      _ => Ok(None),
+    }
+  }
+}
+
+impl Vfs for NodeResolverFs {
+  fn resolve_module(&self, importer: &str, specifier: &str) -> io::Result<Option<String>> {
+    let dir = Path::new(importer).parent().unwrap_or(Path::new("/"));
+    let is_relative = specifier.starts_with('.') || specifier.starts_with('/');
+    let is_commonjs = !is_relative && self.is_commonjs(Path::new(importer))?;
+    let result = if specifier.starts_with('#') {
+      self.resolve_package_imports(dir, specifier, is_commonjs)?
+    } else if is_relative {
+      self.resolve_path(&normalize_path::normalize(&dir.join(specifier)))?
+    } else if specifier.starts_with("node:") {
+      None
+    } else {
+      self.resolve_bare(dir, specifier, is_commonjs)?
+    };
+
+    match result {
+      Some(path) => Ok(Some(path.to_string_lossy().into_owned())),
+      None if is_relative || specifier.starts_with('#') => Err(not_found(specifier, importer)),
+      // Bare specifiers which are not found are left as external
+      None => Ok(None),
+    }
+  }
+
+  fn read_file(&self, path: &str) -> io::Result<String> {
+    std::fs::read_to_string(path)
+  }
+
+  fn has_side_effects(&self, path: &str) -> io::Result<bool> {
+    self.has_side_effects_in_package(Path::new(path))
+  }
+
+  fn read_dir(&self, path: &str) -> io::Result<Vec<String>> {
+    read_std_dir(path)
+  }
+
+  fn read_source_map(&self, path: &str) -> Option<String> {
+    read_std_source_map(path)
+  }
+}
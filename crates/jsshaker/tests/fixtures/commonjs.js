@@ -0,0 +1,23 @@
+'use strict';
+
+const path = require('path');
+const dynamic = require(path.join('a', 'b'));
+
+function used(x) {
+  return x + 1;
+}
+
+function unused() {
+  effect();
+}
+
+exports.a = used(1);
+module.exports.b = function () {
+  return typeof module === 'object' && typeof require;
+};
+
+if (typeof exports === 'object' && typeof module !== 'undefined') {
+  exports.c = this === module.exports;
+} else {
+  unused();
+}
@@ -0,0 +1,24 @@
+#!/usr/bin/env node
+"use client";
+"use strict";
+
+function serverAction(x) {
+  "use server";
+  if (x) return 1;
+  return 2;
+}
+
+function unused() {
+  return 1;
+}
+
+export function main() {
+  const inlineAction = async (a) => {
+    "use server";
+    return a + 1;
+  };
+  const unusedArrow = async (a) => {
+    return a + 1;
+  };
+  return 1;
+}
@@ -0,0 +1,21 @@
+// @exports createApp,default
+
+function createRenderer() {
+  return { render: () => 'rendered' };
+}
+
+export function createApp() {
+  return createRenderer();
+}
+
+export function createSSRApp() {
+  return { ...createRenderer(), ssr: true };
+}
+
+export const version = '1.0.0';
+
+export default {
+  createApp,
+};
+
+console.log('side effect');
@@ -5,4 +5,83 @@ export function *f1(a) {
   if (t) {
     effect(1);
   }
-}
\ No newline at end of file
+}
+
+export function iterate() {
+  function* g() {
+    yield 1;
+    yield 2;
+    return 3;
+  }
+  for (const x of g()) effect(x);
+  const [a, b, c] = g();
+  effect(a, b, c);
+  effect([...g()]);
+}
+
+export function next() {
+  function* g() {
+    yield "a";
+    yield "b";
+  }
+  const it = g();
+  effect(it.next().value);
+  const second = it.next();
+  effect(second.value, second.done);
+  effect(it.next().done);
+}
+
+export function return_and_unused() {
+  function* g() {
+    yield 1;
+    yield effect(2);
+  }
+  const it = g();
+  effect(it.return(4).value, it.next().done);
+  const unused = g();
+}
+
+export function unused_yields(cond) {
+  function* g() {
+    yield 1 + 1;
+    if (false) {
+      yield effect();
+    }
+    if (cond) yield 2;
+    yield 3;
+  }
+  for (const x of g()) effect();
+  const [x, y] = g();
+  effect(y);
+}
+
+export function delegate() {
+  function* inner() {
+    yield 1;
+    yield 2;
+  }
+  function* outer() {
+    yield* inner();
+    yield* [3];
+  }
+  const [p, q, r] = outer(); effect(p, q, r);
+}
+
+export function side_effects() {
+  function* g() {
+    effect(1);
+    yield;
+  }
+  const it = g();
+  it.next();
+}
+
+export function outer_state() {
+  let x = 1;
+  function* g() {
+    yield x;
+  }
+  const it = g();
+  x = 2;
+  for (const v of it) effect(v);
+}
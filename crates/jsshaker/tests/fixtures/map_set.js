@@ -0,0 +1,85 @@
+export function map_basic() {
+  const map = new Map([["a", 1]]);
+  map.set("b", 2);
+  effect(map.get("a"), map.get("b"), map.get("c"), map.has("b"), map.size);
+  map.delete("a");
+  effect(map.has("a"), map.size);
+  map.clear();
+  effect(map.size);
+}
+
+export function map_unused() {
+  const map = new Map();
+  map.set("a", effect());
+  map.set("b", 1);
+}
+
+export function map_unused_entry() {
+  const map = new Map();
+  map.set("a", 1);
+  map.set("b", 2);
+  effect(map.get("a"));
+}
+
+export function map_unknown_key(key) {
+  const map = new Map([["a", 1]]);
+  map.set(key, 2);
+  effect(map.get("a"), map.size);
+}
+
+export function map_non_det(cond) {
+  const map = new Map();
+  if (cond) map.set(1, "x");
+  effect(map.get(1), map.has(1), map.size);
+}
+
+export function map_iterate() {
+  const map = new Map([[1, "a"], [2, "b"]]);
+  for (const [k, v] of map) effect(k, v);
+  map.forEach((v, k) => effect(k, v));
+}
+
+export function map_key_normalize() {
+  const map = new Map([[NaN, "nan"], [0, "zero"]]);
+  effect(map.get(NaN), map.get(-0), map.get("0"));
+}
+
+export function set_basic(unknown) {
+  const set = new Set([1, 2, 2]);
+  set.add(3);
+  effect(set.has(1), set.has(4), set.size);
+  for (const x of set) effect(x);
+  const escaped = new Set();
+  escaped.add(1);
+  unknown(escaped);
+  effect(escaped.has(1));
+}
+
+export function weak_map(unknown) {
+  const key = {};
+  const weak = new WeakMap();
+  weak.set(key, 1);
+  effect(weak.get(key), weak.has(unknown));
+}
+
+export function mutated_while_iterating() {
+  const set = new Set([1]);
+  for (const x of set) {
+    if (x < 3) set.add(x + 1);
+    effect(x);
+  }
+}
+
+export function object_value() {
+  const obj = { a: 1 };
+  const map = new Map();
+  map.set("o", obj);
+  map.get("o").a = 2;
+  effect(obj.a);
+}
+
+export function conditional_delete(cond) {
+  const map = new Map([["a", 1], ["b", 2]]);
+  if (cond) map.delete("a");
+  effect(map.get("a"), map.get("b"), map.size);
+}
@@ -0,0 +1,20 @@
+export function constants() {
+  effect(Math.PI * 2, Math.E > 2, Math.SQRT2 > Math.SQRT1_2);
+}
+
+export function functions() {
+  effect(Math.max(1, 2, 3), Math.min(1, -2), Math.max(), Math.max(1, NaN));
+  effect(Math.abs(-3), Math.floor(1.5), Math.ceil(1.5), Math.round(-2.5), Math.trunc(-1.7));
+  effect(Math.pow(2, 10), Math.sqrt(16), Math.hypot(3, 4), Math.sign(-5));
+  effect(Math.clz32(1), Math.imul(3, 4), Math.fround(5.5), Math.abs("-1"));
+}
+
+export function branch() {
+  if (Math.max(1, 2) > 1) effect(1);
+  else effect(2);
+}
+
+export function not_evaluated(x) {
+  effect(typeof Math.max(1, x), Math.random());
+  const unused = Math.abs(2);
+}
@@ -0,0 +1,86 @@
+export async function resolve_and_reject() {
+  effect(await Promise.resolve(1));
+  const p = Promise.resolve("a");
+  effect(Promise.resolve(p) === p);
+  try {
+    await Promise.reject(2);
+  } catch {}
+  const unused = Promise.resolve(effect());
+}
+
+export async function constructor(cond) {
+  effect(await new Promise((resolve) => resolve(1)));
+  effect(await new Promise((resolve) => {
+    resolve(2);
+    resolve(3);
+  }));
+  effect(await new Promise((resolve) => {
+    if (cond) resolve(4);
+    else resolve(5);
+  }));
+  const unused = new Promise((resolve) => resolve(6));
+}
+
+export async function settled_later() {
+  let resolveLater;
+  const p = new Promise((resolve) => {
+    resolveLater = resolve;
+  });
+  setTimeout(() => resolveLater(1));
+  effect(await p);
+}
+
+export async function then_chain() {
+  effect(await Promise.resolve(1).then((x) => x + 1));
+  effect(await Promise.reject(1).catch((x) => x + 2));
+  effect(await Promise.resolve(1).then().then((x) => x * 10));
+  effect(await Promise.resolve(1).finally(() => {}));
+  effect(await Promise.resolve(1).then(() => Promise.resolve(5)));
+}
+
+export async function callback_order() {
+  let x = 1;
+  Promise.resolve().then(() => {
+    x = 2;
+  });
+  effect(x);
+
+  let y = 1;
+  const p = Promise.resolve().then(() => y);
+  y = 2;
+  effect(await p);
+}
+
+export async function combinators() {
+  effect(await Promise.all([1, Promise.resolve(2)]));
+  effect(await Promise.race([Promise.resolve(3), 4]));
+  effect(await Promise.any([Promise.reject(0), 5]));
+  const [settled] = await Promise.allSettled([Promise.resolve(6)]);
+  effect(settled.status, settled.value);
+}
+
+export async function executor_throws() {
+  try {
+    await new Promise(() => {
+      throw 1;
+    });
+  } catch {
+    effect();
+  }
+}
+
+export async function finally_throws() {
+  effect(await Promise.resolve(1).finally(() => {
+    throw 7;
+  }).catch((e) => e));
+}
+
+export async function callback_throws() {
+  try {
+    await Promise.resolve(1).then(() => {
+      throw 2;
+    });
+  } catch {
+    effect();
+  }
+}
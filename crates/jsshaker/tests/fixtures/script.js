@@ -0,0 +1,21 @@
+// @script
+var exposed = 1;
+function helper() {
+  return 2;
+}
+let lexical = 3;
+
+(function (root, factory) {
+  if (typeof module === "object") {
+    module.exports = factory();
+  } else {
+    root.lib = factory();
+  }
+})(this, function () {
+  function unused() {}
+  var a = 1;
+  return { a: a };
+});
+
+x = 1;
+delete y;
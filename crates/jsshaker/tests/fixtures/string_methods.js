@@ -0,0 +1,40 @@
+export function case_and_trim() {
+  effect("abc".toUpperCase(), "ABC".toLowerCase());
+  effect("  a b  ".trim(), "  a".trimStart(), "a  ".trimEnd());
+  effect("abc".toString(), "abc".valueOf());
+}
+
+export function search() {
+  effect("xyz".startsWith("x"), "xyz".endsWith("y"), "xyz".includes("yz"));
+  effect("abcabc".indexOf("c"), "abcabc".lastIndexOf("c"), "abc".indexOf("d"));
+  effect("abc".startsWith("b", 1), "abc".endsWith("a", 1));
+}
+
+export function characters() {
+  effect("abc".at(-1), "abc".at(5), "abc".charAt(1), "abc".charCodeAt(0));
+  effect("😀".codePointAt(0), "😀".charCodeAt(0));
+}
+
+export function substrings() {
+  effect("hello".slice(1, -1), "hello".substring(3, 1), "hello".substr(-3, 2));
+  effect("a".concat("b", 1), "ab".repeat(3), "5".padStart(3, "0"), "5".padEnd(3));
+  effect("a-b-a".replace("a", "c"), "a-b-a".replaceAll("a", "c"));
+}
+
+export function split() {
+  const [a, b, c] = "x-y".split("-");
+  effect(a, b, c);
+  const [d, e] = "ab".split("");
+  effect(d, e);
+}
+
+export function not_evaluated(s) {
+  effect(typeof s.includes("a"), typeof "abc".indexOf(s), typeof s.split(",")[0]);
+  effect("a".replace("a", "$&"), "a".repeat(-1));
+  const unused = s.toUpperCase();
+}
+
+export function lone_surrogates() {
+  effect("\uD800".isWellFormed(), "\uD800".charCodeAt(0), "\uD800x".toUpperCase());
+  effect("a".concat("\uDC00"), "a\uD800".includes("a"));
+}
@@ -0,0 +1,20 @@
+let state = "initial";
+export const before = state;
+
+function pure() {
+  return 1;
+}
+const x = pure();
+
+await 0;
+
+export const after = x + 1;
+export const value = await pure();
+
+// Other modules may run during `await`
+state = "changed";
+export const current = state;
+
+for await (const item of unknown) {
+  console.log(item);
+}
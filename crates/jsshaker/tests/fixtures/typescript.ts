@@ -0,0 +1,66 @@
+import type { Foo } from 'types';
+import { type Bar, baz, onlyAsType } from 'mixed';
+import fs = require('fs');
+
+type Alias = Foo | Bar;
+interface Shape {
+  area(): number;
+}
+declare const injected: string;
+declare module 'ambient' {}
+
+export enum Direction {
+  Up = 1,
+  Down,
+  Left = Up + 10,
+  Right = "RIGHT",
+}
+
+enum Unused {
+  A,
+  B,
+}
+
+export function overloaded(a: string): string;
+export function overloaded(a: number): number;
+export function overloaded(a: any): any {
+  return a as any;
+}
+
+export class Circle<T> implements Shape {
+  declare tag: string;
+  private cache!: number;
+
+  constructor(public readonly radius: number, private name?: string) {
+    effect(this.name);
+  }
+
+  area(): number {
+    return (this.radius satisfies number) * this.radius * Math.PI;
+  }
+}
+
+export abstract class Base {
+  abstract run(): void;
+  constructor(protected value: number) {}
+}
+
+export class Derived extends Base {
+  constructor(value: number, public extra: number) {
+    super(value);
+  }
+  run() {}
+}
+
+export function main(x: onlyAsType, y?: Alias) {
+  let n: number = <number>x!;
+  (y as any) = n;
+  return baz<string>(n, y, Direction.Down, fs.readFileSync);
+}
+
+export const dir = Direction.Up;
+export type { Alias };
+
+export function chain(a?: { b?: { c: number } }) {
+  return [a?.b!.c, (a as any).b, a!?.b];
+}
@@ -0,0 +1,7 @@
+// Non-constant initializers referencing other members can't be evaluated outside of the enum
+export enum Computed {
+  A = Math.random(),
+  B = A * 2,
+}
+
+export const value = Computed.B;
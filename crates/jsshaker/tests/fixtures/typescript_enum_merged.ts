@@ -0,0 +1,23 @@
+export enum Color {
+  Red,
+  Green,
+}
+
+export enum Color {
+  Blue = 2,
+  Mixed = Red | Blue,
+}
+
+enum Local {
+  A = "a",
+}
+
+enum Local {
+  B = A + "b",
+}
+
+export const local = [Local.A, Local.B];
+
+export function effects() {
+  effect(Color.Red, Color.Blue, Color.Mixed, Color[0]);
+}
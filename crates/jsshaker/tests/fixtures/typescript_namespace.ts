@@ -0,0 +1,35 @@
+export namespace Config {
+  export const name: string = "config";
+  export let count = 0;
+  export function increment() {
+    count++;
+    return { count };
+  }
+  export enum Level {
+    Low,
+    High,
+  }
+  export interface Options {}
+  const unused = 1;
+  export namespace Inner {
+    export const value = name + "!";
+  }
+}
+
+namespace Local.Nested {
+  export const used = 1;
+  export const unused = 2;
+}
+
+export class Merged {}
+export namespace Merged {
+  export const tag = "merged";
+}
+
+namespace TypeOnly {
+  export type T = string;
+}
+
+export function main() {
+  return [Config.increment(), Config.Level.High, Config.Inner.value, Local.Nested.used, Merged.tag];
+}
@@ -0,0 +1,8 @@
+// The parameter `N` shadows the namespace, which is accessed as `N.x` in the lowered code.
+// The module is left untransformed instead.
+export namespace N {
+  export let x: number = 1;
+  export function f(N: number) {
+    return x + N;
+  }
+}
@@ -0,0 +1,23 @@
+// @script
+function main(obj) {
+  let a = 1, b = 2, unused = 3;
+  with (obj) {
+    effect(a);
+    b = 3;
+    let c = 1;
+    effect(c);
+    if (false) effect("dead");
+  }
+  effect(b);
+  with (obj) {
+    let d = 1;
+  }
+}
+
+function f(obj) {
+  let x = 1;
+  with (obj) {
+    x = 2;
+  }
+  return x;
+}
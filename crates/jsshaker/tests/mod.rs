@@ -1,44 +1,353 @@
-use std::fs;
+use std::{
+  cell::{Cell, RefCell},
+  collections::{HashMap, HashSet},
+  fs, io,
+  rc::Rc,
+};
 
 use insta::{assert_snapshot, glob};
-use jsshaker::{JsShakerOptions, TreeShakeConfig, tree_shake, vfs::SingleFileFs};
+use jsshaker::{
+  JsShakerEntry, JsShakerOptions, JsShakerReturn, JsShakerSession, TreeShakeConfig, tree_shake,
+  vfs::{MultiModuleFs, NodeResolverFs, SingleFileFs, Vfs},
+};
 use oxc::{
   codegen::{CodegenOptions, CommentOptions},
   minifier::MinifierOptions,
+  span::SourceType,
 };
 
-fn do_tree_shake(input: String) -> String {
+struct TestFs {
+  source: SingleFileFs,
+  source_type: Option<SourceType>,
+}
+
+impl Vfs for TestFs {
+  fn resolve_module(&self, importer: &str, specifier: &str) -> io::Result<Option<String>> {
+    self.source.resolve_module(importer, specifier)
+  }
+
+  fn read_file(&self, path: &str) -> io::Result<String> {
+    self.source.read_file(path)
+  }
+
+  fn source_type(&self, _path: &str) -> Option<SourceType> {
+    self.source_type
+  }
+}
+
+fn test_config() -> TreeShakeConfig {
+  let mut config = TreeShakeConfig::recommended();
+  config.unknown_global_side_effects = true;
+  config.advanced = true;
+  config
+}
+
+fn do_tree_shake(input: String, source_type: Option<SourceType>) -> String {
   let do_minify = input.contains("@minify");
   let react_jsx = input.contains("@react-jsx");
-  let result = tree_shake(
-    JsShakerOptions {
-      vfs: SingleFileFs(input),
-      config: {
-        let mut config = TreeShakeConfig::recommended();
-        if react_jsx {
-          config.jsx = jsshaker::TreeShakeJsxPreset::React;
-        }
-        config.unknown_global_side_effects = true;
-        config.advanced = true;
-        config
-      },
-      minify_options: do_minify.then(|| MinifierOptions { mangle: None, ..Default::default() }),
-      codegen_options: CodegenOptions { comments: CommentOptions::default(), ..Default::default() },
-      source_map: false,
+  let script = input.contains("@script");
+  let exports = input.lines().find_map(|line| {
+    let exports = line.trim().strip_prefix("// @exports ")?;
+    Some(exports.split(',').map(|name| name.trim().to_string()).collect())
+  });
+  let entry = match exports {
+    Some(exports) => JsShakerEntry::with_exports(SingleFileFs::ENTRY_PATH, exports),
+    None => JsShakerEntry::new(SingleFileFs::ENTRY_PATH),
+  };
+  let result = tree_shake(JsShakerOptions {
+    vfs: TestFs { source: SingleFileFs(input), source_type },
+    entries: vec![entry],
+    config: {
+      let mut config = test_config();
+      if react_jsx {
+        config.jsx = jsshaker::TreeShakeJsxPreset::React;
+      }
+      if script {
+        config.source_kind = jsshaker::TreeShakeSourceKind::Script;
+      }
+      config
     },
-    SingleFileFs::ENTRY_PATH.to_string(),
-  );
+    minify_options: do_minify.then(|| MinifierOptions { mangle: None, ..Default::default() }),
+    codegen_options: CodegenOptions { comments: CommentOptions::default(), ..Default::default() },
+    source_map: false,
+  });
   result.codegen_return[SingleFileFs::ENTRY_PATH].code.clone()
 }
 
 #[test]
 fn test() {
-  glob!("fixtures/**/*.js", |path| {
+  glob!("fixtures/**/*.{js,ts,tsx}", |path| {
+    let input = fs::read_to_string(path).unwrap();
+    let source_type = SourceType::from_path(path)
+      .ok()
+      .filter(|ty| ty.is_typescript())
+      .map(|ty| ty.with_module(true));
+    let mut settings = insta::Settings::clone_current();
+    settings.set_prepend_module_to_snapshot(false);
+    settings.bind(|| {
+      assert_snapshot!(do_tree_shake(input, source_type));
+    })
+  });
+}
+
+/// Modules are separated by `// @file <path>` lines. Entries are listed by
+/// `// @entry <path> [side-effects-only | exports=a,b]` lines, which default to `/index.js`.
+/// Modules listed by `// @side-effect-free <path>` lines are side-effect-free, like packages with
+/// `"sideEffects": false`.
+fn parse_multi_module_input(input: &str) -> (HashMap<String, String>, Vec<JsShakerEntry>) {
+  let mut sources = HashMap::new();
+  let mut entries = vec![];
+  let mut current: Option<(String, String)> = None;
+  for line in input.lines() {
+    if let Some(path) = line.strip_prefix("// @file ") {
+      sources.extend(current.take());
+      current = Some((path.trim().to_string(), String::new()));
+    } else if let Some((_, source)) = &mut current {
+      source.push_str(line);
+      source.push('\n');
+    } else if let Some(entry) = line.strip_prefix("// @entry ") {
+      let mut parts = entry.split_whitespace();
+      let path = parts.next().unwrap();
+      entries.push(match parts.next() {
+        Some("side-effects-only") => JsShakerEntry::side_effects_only(path),
+        Some(exports) => JsShakerEntry::with_exports(
+          path,
+          exports.strip_prefix("exports=").unwrap().split(',').map(String::from).collect(),
+        ),
+        None => JsShakerEntry::new(path),
+      });
+    }
+  }
+  sources.extend(current);
+  if entries.is_empty() {
+    entries.push(JsShakerEntry::new("/index.js"));
+  }
+  (sources, entries)
+}
+
+struct MultiModuleTestFs {
+  sources: MultiModuleFs,
+  side_effect_free: HashSet<String>,
+}
+
+impl Vfs for MultiModuleTestFs {
+  fn resolve_module(&self, importer: &str, specifier: &str) -> io::Result<Option<String>> {
+    self.sources.resolve_module(importer, specifier)
+  }
+
+  fn read_file(&self, path: &str) -> io::Result<String> {
+    self.sources.read_file(path)
+  }
+
+  fn has_side_effects(&self, path: &str) -> io::Result<bool> {
+    Ok(!self.side_effect_free.contains(path))
+  }
+
+  fn read_dir(&self, path: &str) -> io::Result<Vec<String>> {
+    self.sources.read_dir(path)
+  }
+}
+
+fn do_tree_shake_multi_module(input: &str) -> String {
+  let (sources, entries) = parse_multi_module_input(input);
+  let side_effect_free = input
+    .lines()
+    .filter_map(|line| Some(line.strip_prefix("// @side-effect-free ")?.trim().to_string()))
+    .collect();
+  let result = tree_shake(JsShakerOptions {
+    vfs: MultiModuleTestFs { sources: MultiModuleFs::new(sources), side_effect_free },
+    entries,
+    config: TreeShakeConfig {
+      glob_dynamic_import: input.contains("@glob-dynamic-import"),
+      ..test_config()
+    },
+    minify_options: None,
+    codegen_options: CodegenOptions { comments: CommentOptions::default(), ..Default::default() },
+    source_map: false,
+  });
+  format_outputs(result)
+}
+
+/// Outputs of all the modules sorted by path, followed by the diagnostics
+fn format_outputs(result: JsShakerReturn) -> String {
+  let mut outputs = result.codegen_return.into_iter().collect::<Vec<_>>();
+  outputs.sort_by(|(a, _), (b, _)| a.cmp(b));
+  let mut output = String::new();
+  for (path, codegen_return) in outputs {
+    output.push_str(&format!("// @file {path}\n{}\n", codegen_return.code.trim_end()));
+  }
+  for diagnostic in result.diagnostics {
+    output.push_str(&format!("// @diagnostic {diagnostic}\n"));
+  }
+  output
+}
+
+#[test]
+fn multi_module() {
+  glob!("multi_module/*.js", |path| {
     let input = fs::read_to_string(path).unwrap();
     let mut settings = insta::Settings::clone_current();
     settings.set_prepend_module_to_snapshot(false);
     settings.bind(|| {
-      assert_snapshot!(do_tree_shake(input));
+      assert_snapshot!(do_tree_shake_multi_module(&input));
     })
   });
 }
+
+/// Fails the first read of the entry, which is by the preparsing. Thus the imported modules are not
+/// discovered, and all the modules are parsed serially by the analyzer.
+struct SerialParseFs {
+  sources: MultiModuleFs,
+  entry_read: Cell<bool>,
+}
+
+impl Vfs for SerialParseFs {
+  fn resolve_module(&self, importer: &str, specifier: &str) -> io::Result<Option<String>> {
+    self.sources.resolve_module(importer, specifier)
+  }
+
+  fn read_file(&self, path: &str) -> io::Result<String> {
+    if path == "/index.js" && !self.entry_read.replace(true) {
+      return Err(io::Error::other("Not read by the preparsing"));
+    }
+    self.sources.read_file(path)
+  }
+}
+
+#[test]
+fn preparse_matches_serial_parse() {
+  let input = fs::read_to_string("tests/multi_module/preparse.js").unwrap();
+  let (sources, entries) = parse_multi_module_input(&input);
+  let options = |vfs| JsShakerOptions {
+    vfs,
+    entries: entries.clone(),
+    config: test_config(),
+    minify_options: None,
+    codegen_options: CodegenOptions { comments: CommentOptions::default(), ..Default::default() },
+    source_map: false,
+  };
+  let preparsed = format_outputs(tree_shake(options(
+    Rc::new(MultiModuleFs::new(sources.clone())) as Rc<dyn Vfs>
+  )));
+  let serial = format_outputs(tree_shake(options(Rc::new(SerialParseFs {
+    sources: MultiModuleFs::new(sources),
+    entry_read: Cell::new(false),
+  }))));
+  assert_eq!(preparsed, serial);
+}
+
+/// Records the paths read, to check which modules are reused from the cache
+struct ReadLogFs {
+  sources: MultiModuleFs,
+  reads: RefCell<Vec<String>>,
+}
+
+impl Vfs for ReadLogFs {
+  fn resolve_module(&self, importer: &str, specifier: &str) -> io::Result<Option<String>> {
+    self.sources.resolve_module(importer, specifier)
+  }
+
+  fn read_file(&self, path: &str) -> io::Result<String> {
+    self.reads.borrow_mut().push(path.to_string());
+    self.sources.read_file(path)
+  }
+}
+
+#[test]
+fn session_update() {
+  let sources = HashMap::from([
+    (
+      "/index.js".to_string(),
+      "import { a } from './a.js';\nimport { b } from './b.js';\nimport data from './data.json';\n\
+       export const sum = a + b + data.n;\n\
+       export const load = () => import('./d.js');\n"
+        .to_string(),
+    ),
+    ("/a.js".to_string(), "export const a = 1;\n".to_string()),
+    ("/b.js".to_string(), "export const b = 2;\n".to_string()),
+    ("/c.js".to_string(), "exports.c = 3;\n".to_string()),
+    ("/d.js".to_string(), "exports.d = require('./c.js').c + 1;\n".to_string()),
+    ("/data.json".to_string(), r#"{ "n": 5 }"#.to_string()),
+  ]);
+  let mut session = JsShakerSession::new(JsShakerOptions {
+    vfs: ReadLogFs { sources: MultiModuleFs::new(sources), reads: Default::default() },
+    entries: vec![JsShakerEntry::new("/index.js")],
+    config: test_config(),
+    minify_options: None,
+    codegen_options: CodegenOptions { comments: CommentOptions::default(), ..Default::default() },
+    source_map: false,
+  });
+  let first = format_outputs(session.run());
+  let mut reads = session.vfs().reads.take();
+  reads.sort();
+  assert_eq!(reads, ["/a.js", "/b.js", "/c.js", "/d.js", "/data.json", "/index.js"]);
+
+  session.vfs_mut().sources.sources.insert("/b.js".into(), "export const b = 40;\n".into());
+  let second = format_outputs(session.update(&["/b.js".into()]));
+  // Only the changed module is read again, the others are reused from the cache
+  assert_eq!(session.vfs().reads.take(), ["/b.js"]);
+
+  // Including the modules loaded lazily
+  session.vfs_mut().sources.sources.insert("/data.json".into(), r#"{ "n": 50 }"#.into());
+  let third = format_outputs(session.update(&["/data.json".into()]));
+  assert_eq!(session.vfs().reads.take(), ["/data.json"]);
+
+  let mut settings = insta::Settings::clone_current();
+  settings.set_prepend_module_to_snapshot(false);
+  settings.bind(|| {
+    assert_snapshot!(format!("{first}// @update /b.js\n{second}// @update /data.json\n{third}"));
+  })
+}
+
+#[test]
+fn node_resolver() {
+  let dir = fs::canonicalize("tests/node_resolver").unwrap();
+  let dir = dir.to_str().unwrap();
+  let result = tree_shake(JsShakerOptions {
+    vfs: NodeResolverFs::default(),
+    entries: vec![JsShakerEntry::new(format!("{dir}/index.js"))],
+    config: test_config(),
+    minify_options: None,
+    codegen_options: CodegenOptions { comments: CommentOptions::default(), ..Default::default() },
+    source_map: false,
+  });
+  let mut settings = insta::Settings::clone_current();
+  settings.set_prepend_module_to_snapshot(false);
+  settings.bind(|| {
+    assert_snapshot!(format_outputs(result).replace(dir, "<dir>"));
+  })
+}
+
+#[test]
+fn source_map_composition() {
+  let source = "export const a = 1;\nexport const b = 2;\nexport const c = 3;\n";
+  // Line 0 maps to line 0 of `original.ts`, line 2 maps to line 5, and line 1 has no mappings
+  let input_map = r#"{"version":3,"sources":["original.ts"],"names":[],"mappings":"AAAA;;AAKA"}"#;
+  let result = tree_shake(JsShakerOptions {
+    vfs: MultiModuleFs::new(HashMap::from([("/index.js".to_string(), source.to_string())]))
+      .with_source_maps(HashMap::from([("/index.js".to_string(), input_map.to_string())])),
+    entries: vec![JsShakerEntry::new("/index.js")],
+    config: test_config(),
+    minify_options: None,
+    codegen_options: CodegenOptions::default(),
+    source_map: true,
+  });
+  let output = &result.codegen_return["/index.js"];
+  let map = output.map.as_ref().unwrap();
+  let mut mappings = String::new();
+  for token in map.get_tokens() {
+    mappings.push_str(&format!(
+      "{}:{} -> {}:{}:{}\n",
+      token.get_dst_line(),
+      token.get_dst_col(),
+      map.get_source(token.get_source_id().unwrap()).unwrap(),
+      token.get_src_line(),
+      token.get_src_col(),
+    ));
+  }
+  let mut settings = insta::Settings::clone_current();
+  settings.set_prepend_module_to_snapshot(false);
+  settings.bind(|| {
+    assert_snapshot!(format!("{}\n{mappings}", output.code));
+  })
+}
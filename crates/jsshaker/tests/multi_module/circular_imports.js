@@ -0,0 +1,23 @@
+// @file /index.js
+import { a, getB } from "./a.js";
+effect(a, getB());
+
+// @file /a.js
+import { b, readA } from "./b.js";
+export const a = { a: 1 };
+export function getB() {
+  return b;
+}
+effect(readA());
+
+// @file /b.js
+import { a } from "./a.js";
+try {
+  // Read before `a.js` is initialized, which throws
+  effect(a);
+} catch {}
+export const b = { b: 2 };
+export function readA() {
+  // Read after `a.js` is initialized
+  return a.a;
+}
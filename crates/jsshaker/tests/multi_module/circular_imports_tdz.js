@@ -0,0 +1,12 @@
+// @file /index.js
+import { a } from "./a.js";
+effect(a);
+
+// @file /a.js
+import "./b.js";
+export let a = 1;
+
+// @file /b.js
+import { a } from "./a.js";
+// `a.js` is not initialized yet, thus this throws a `ReferenceError`
+effect(a);
@@ -0,0 +1,20 @@
+// @file /index.js
+const { add } = require("./math.js");
+const config = require("./config.js");
+const unused = require("./unused.js");
+effect(add(effect(), config.base));
+
+// @file /math.js
+const { double } = require("./utils.js");
+exports.add = (a, b) => double(a) + b;
+exports.unused = () => effect("unused");
+
+// @file /utils.js
+exports.double = (x) => x * 2;
+
+// @file /config.js
+module.exports = { base: 10 };
+
+// @file /unused.js
+exports.value = { answer: 42 };
+exports.log = () => effect("unused");
@@ -0,0 +1,23 @@
+// @file /index.js
+await import("./a.js");
+effect("after");
+
+export async function run() {
+  await import("./c.js");
+  effect("after c");
+}
+
+export async function load() {
+  const { value } = await import("./b.js");
+  effect("loaded", value);
+}
+
+// @file /a.js
+effect("a");
+
+// @file /b.js
+export const value = 1;
+export const unused = 2;
+
+// @file /c.js
+effect("c");
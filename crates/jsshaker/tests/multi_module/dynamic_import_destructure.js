@@ -0,0 +1,12 @@
+// @file /index.js
+export async function main() {
+  const { a } = await import("./m.js");
+  effect(a);
+}
+
+// @file /m.js
+export const a = { x: 1 };
+export const b = { y: 2 };
+export function c() {
+  return b;
+}
@@ -0,0 +1,22 @@
+// @file /index.js
+export async function external(cond) {
+  const m = await import(cond ? "./a.js" : "some-external-pkg");
+  return m.v;
+}
+
+export async function missing(cond) {
+  const m = await import(cond ? "./a.js" : "./missing.js");
+  return m.v;
+}
+
+export async function resolved(cond) {
+  const m = await import(cond ? "./a.js" : "./b.js");
+  return m.v;
+}
+
+// @file /a.js
+export const v = 1;
+export const unused = 2;
+
+// @file /b.js
+export const v = 1;
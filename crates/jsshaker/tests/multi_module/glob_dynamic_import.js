@@ -0,0 +1,17 @@
+// @glob-dynamic-import
+
+// @file /index.js
+export async function load(name) {
+  const m = await import(`./locales/${name}.js`);
+  return m.message;
+}
+
+// @file /locales/en.js
+export const message = "hello";
+export const unused = "unused";
+
+// @file /locales/fr.js
+export const message = "bonjour";
+
+// @file /locales/readme.md
+Not a module
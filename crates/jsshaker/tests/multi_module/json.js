@@ -0,0 +1,20 @@
+// @entry /index.js
+// @entry /cjs.js
+
+// @file /index.js
+import data from "./data.json";
+import bad from "./bad.json";
+effect(data.used);
+export const x = bad;
+
+// @file /cjs.js
+module.exports = require("./d.json").a;
+
+// @file /d.json
+{ "a": { "x": 1 }, "b": 2 }
+
+// @file /data.json
+{ "used": [1, 2], "unused": "text" }
+
+// @file /bad.json
+{ "a": 1,, }
@@ -0,0 +1,21 @@
+// @entry /a.js
+// @entry /b.js
+// @entry /c.js side-effects-only
+
+// @file /a.js
+import { x } from "./shared.js";
+effect(x);
+
+// @file /b.js
+import { y } from "./shared.js";
+effect(y);
+
+// @file /c.js
+import { z } from "./shared.js";
+export const unused = z;
+effect("c");
+
+// @file /shared.js
+export const x = { x: 1 };
+export const y = { y: 2 };
+export const z = { z: 3 };
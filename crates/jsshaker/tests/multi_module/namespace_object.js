@@ -0,0 +1,37 @@
+// @file /index.js
+import * as ns from "./m.js";
+
+export function to_string_tag() {
+  effect(ns[Symbol.toStringTag], Object.prototype.toString.call(ns));
+  effect(ns[Symbol.toStringTag] === "Module", Symbol.toStringTag in ns, ns[Symbol.iterator]);
+}
+
+export function assign() {
+  try {
+    ns.a = 2;
+  } catch {
+    effect("assign failed");
+  }
+  effect(ns.a);
+}
+
+export function delete_property() {
+  try {
+    delete ns.a;
+  } catch {
+    effect("delete failed");
+  }
+  effect(delete ns.missing);
+}
+
+export function enumerate() {
+  for (const key in ns) {
+    effect(key, ns[key]);
+  }
+  effect(Object.keys(ns));
+}
+
+// @file /m.js
+export const a = 1;
+export const b = 2;
+export default 3;
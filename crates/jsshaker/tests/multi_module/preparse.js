@@ -0,0 +1,29 @@
+// @file /index.js
+import { add, unused } from "./math.ts";
+import config from "./config.json";
+import { helper } from "./helper.js";
+import { missing } from "./missing.js";
+import { external } from "external";
+export const result = add(config.base, helper());
+export { missing, external };
+
+// @file /math.ts
+import { scale } from "./scale";
+export enum Mode { Fast, Slow }
+export function add(a: number, b: number): number {
+  return scale(a) + b;
+}
+export const unused: string = "unused";
+
+// @file /scale.ts
+export const scale = (x: number) => x * 2;
+
+// @file /config.json
+{ "base": 1, "other": 2 }
+
+// @file /helper.js
+import { shared } from "./shared.js";
+export const helper = () => shared;
+
+// @file /shared.js
+export const shared = 3;
@@ -0,0 +1,21 @@
+// @side-effect-free /pure.js
+// @side-effect-free /pure-used.js
+
+// @file /index.js
+import { unused } from "./pure.js";
+import "./pure.js";
+import { used } from "./pure-used.js";
+import { effect } from "./impure.js";
+export const value = used;
+
+// @file /pure.js
+console.log("side-effect-free");
+export const unused = 1;
+
+// @file /pure-used.js
+console.log("side effect");
+export const used = 2;
+
+// @file /impure.js
+console.log("side effect");
+export const effect = 3;
@@ -0,0 +1,15 @@
+import { a } from "pkg";
+import { b } from "pkg/feature";
+import { c } from "pkg/utils/c.js";
+import { hidden } from "pkg/lib/hidden.js";
+import { d } from "#internal";
+import { e } from "#dep";
+import { f } from "legacy";
+import { g } from "bad";
+import { h } from "pure";
+import "patterns";
+import "badglob";
+import { nested } from "./src/nested.js";
+import { required } from "./require.cjs";
+
+export const values = [a, b, c, hidden, d, e, f, g, nested, required];
@@ -0,0 +1 @@
+exports.required = require("pkg").a;
@@ -0,0 +1,2 @@
+export const d = "internal";
+export const unused = "unused";
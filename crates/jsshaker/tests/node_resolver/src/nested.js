@@ -0,0 +1,5 @@
+import { only } from "pkg/only";
+// Not exported by the nearest package, the outer one is not searched
+import { b } from "pkg/feature";
+
+export const nested = [only, b];
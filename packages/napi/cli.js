@@ -5,6 +5,11 @@ const { shakeFsModule, shakeSingleModule } = require("./index.js");
 const { writeFile, mkdir, readFile } = require("node:fs/promises");
 const { join, dirname } = require("node:path");
 
+function formatDiagnostic({ severity, code, message, path, startLine, startColumn }) {
+  const location = path ? ` at ${path}${startLine ? `:${startLine}:${startColumn}` : ""}` : "";
+  return `${severity}[${code}]: ${message}${location}`;
+}
+
 (async () => {
   const { values, positionals } = parseArgs({
     options: {
@@ -41,8 +46,8 @@ const { join, dirname } = require("node:path");
   if (!values.single) {
     const result = shakeFsModule(positionals[0], options);
 
-    for (const message of result.diagnostics) {
-      console.warn(message);
+    for (const diagnostic of result.diagnostics) {
+      console.warn(formatDiagnostic(diagnostic));
     }
 
     for (let [path, { code }] of Object.entries(result.output)) {
@@ -56,8 +61,8 @@ const { join, dirname } = require("node:path");
     const content = await readFile(positionals[0], "utf-8");
     const result = shakeSingleModule(content, options);
 
-    for (const message of result.diagnostics) {
-      console.warn(message);
+    for (const diagnostic of result.diagnostics) {
+      console.warn(formatDiagnostic(diagnostic));
     }
 
     await writeFile(values.outdir || "out.js", result.output.code);
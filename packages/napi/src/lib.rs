@@ -3,7 +3,8 @@
 use std::collections::HashMap;
 
 use jsshaker::{
-  JsShakerOptions, TreeShakeConfig, TreeShakeJsxPreset,
+  EntryExports, JsShakerEntry, JsShakerOptions, TreeShakeConfig, TreeShakeJsxPreset,
+  TreeShakeSourceKind,
   vfs::{MultiModuleFs, SingleFileFs, StdFs, Vfs},
 };
 use napi_derive::napi;
@@ -16,6 +17,8 @@ pub struct Options {
   pub minify: Option<bool>,
   #[napi(ts_type = "'react'")]
   pub jsx: Option<String>,
+  #[napi(ts_type = "'module' | 'script' | 'auto'")]
+  pub source_kind: Option<String>,
   pub source_map: Option<bool>,
 
   pub advanced: Option<bool>,
@@ -24,6 +27,8 @@ pub struct Options {
   #[napi(ts_type = "'enabled' | 'disabled' | 'only'")]
   pub property_mangling: Option<String>,
   pub branch_folding: Option<bool>,
+  /// Expand `import(`./dir/${name}.js`)` to all the matching files
+  pub glob_dynamic_import: Option<bool>,
 
   pub max_recursion_depth: Option<u32>,
   pub remember_exhausted_variables: Option<bool>,
@@ -33,12 +38,70 @@ pub struct Options {
   pub enable_mangling_stats: Option<bool>,
 }
 
+#[napi(object)]
+pub struct Entry {
+  pub path: String,
+  /// Only preserve the side effects of this entry, its exports are considered unused
+  pub side_effects_only: Option<bool>,
+  /// Names of the exports to keep, all exports are kept if not specified
+  pub exports: Option<Vec<String>>,
+}
+
+impl From<Entry> for JsShakerEntry {
+  fn from(value: Entry) -> Self {
+    JsShakerEntry {
+      path: value.path,
+      exports: match (value.side_effects_only, value.exports) {
+        (Some(true), _) => EntryExports::SideEffectsOnly,
+        (_, Some(exports)) => EntryExports::Only(exports),
+        _ => EntryExports::All,
+      },
+    }
+  }
+}
+
 #[napi(object)]
 pub struct Chunk {
   pub code: String,
   pub source_map_json: Option<String>,
 }
 
+#[napi(object)]
+pub struct Diagnostic {
+  #[napi(ts_type = "'error' | 'warning' | 'info'")]
+  pub severity: String,
+  /// A stable identifier, e.g. `parse-error` or `unresolved-module`
+  pub code: String,
+  pub message: String,
+  pub path: Option<String>,
+  /// Byte offsets in the source text
+  pub start: Option<u32>,
+  pub end: Option<u32>,
+  /// 1-based
+  pub start_line: Option<u32>,
+  pub start_column: Option<u32>,
+  pub end_line: Option<u32>,
+  pub end_column: Option<u32>,
+}
+
+impl From<jsshaker::Diagnostic> for Diagnostic {
+  fn from(value: jsshaker::Diagnostic) -> Self {
+    let location = value.location;
+    Diagnostic {
+      severity: value.severity.as_str().to_string(),
+      code: value.code.as_str().to_string(),
+      message: value.message,
+      path: value.path,
+      start: location.map(|l| l.start),
+      end: location.map(|l| l.end),
+      start_line: location.map(|l| l.start_line),
+      start_column: location.map(|l| l.start_column),
+      end_line: location.map(|l| l.end_line),
+      end_column: location.map(|l| l.end_column),
+    }
+  }
+}
+
 #[napi(object)]
 pub struct Stat {
   pub fn_cache: Option<FnCacheStat>,
@@ -80,7 +143,11 @@ impl From<oxc::codegen::CodegenReturn> for Chunk {
   }
 }
 
-fn resolve_options<F: Vfs>(vfs: F, options: Options) -> JsShakerOptions<F> {
+fn resolve_options<F: Vfs>(
+  vfs: F,
+  entries: Vec<JsShakerEntry>,
+  options: Options,
+) -> JsShakerOptions<F> {
   let preset = options.preset.as_deref().unwrap_or("recommended");
 
   let mut config = match preset {
@@ -93,6 +160,14 @@ fn resolve_options<F: Vfs>(vfs: F, options: Options) -> JsShakerOptions<F> {
   if options.jsx.as_deref() == Some("react") {
     config.jsx = TreeShakeJsxPreset::React;
   }
+  if let Some(source_kind) = options.source_kind.as_deref() {
+    config.source_kind = match source_kind {
+      "module" => TreeShakeSourceKind::Module,
+      "script" => TreeShakeSourceKind::Script,
+      "auto" => TreeShakeSourceKind::Auto,
+      _ => panic!("Invalid source_kind option {:?}", source_kind),
+    };
+  }
 
   config.advanced = options.advanced.unwrap_or(false);
   if let Some(constant_folding) = options.constant_folding.as_deref() {
@@ -114,6 +189,9 @@ fn resolve_options<F: Vfs>(vfs: F, options: Options) -> JsShakerOptions<F> {
   if let Some(branch_folding) = options.branch_folding {
     config.branch_folding = branch_folding;
   }
+  if let Some(glob_dynamic_import) = options.glob_dynamic_import {
+    config.glob_dynamic_import = glob_dynamic_import;
+  }
 
   if let Some(depth) = options.max_recursion_depth {
     config.max_recursion_depth = depth as usize;
@@ -140,6 +218,7 @@ fn resolve_options<F: Vfs>(vfs: F, options: Options) -> JsShakerOptions<F> {
 
   JsShakerOptions {
     vfs,
+    entries,
     config,
     minify_options,
     codegen_options: CodegenOptions { minify, ..Default::default() },
@@ -182,19 +261,20 @@ fn convert_stats(
 #[napi(object)]
 pub struct SingleModuleResult {
   pub output: Chunk,
-  pub diagnostics: Vec<String>,
+  pub diagnostics: Vec<Diagnostic>,
   pub stat: Stat,
 }
 
 #[napi]
 pub fn shake_single_module(source_text: String, options: Options) -> SingleModuleResult {
-  let mut result = jsshaker::tree_shake(
-    resolve_options(SingleFileFs(source_text), options),
-    SingleFileFs::ENTRY_PATH.to_string(),
-  );
+  let mut result = jsshaker::tree_shake(resolve_options(
+    SingleFileFs(source_text),
+    vec![JsShakerEntry::new(SingleFileFs::ENTRY_PATH)],
+    options,
+  ));
   SingleModuleResult {
     output: result.codegen_return.remove(SingleFileFs::ENTRY_PATH).unwrap().into(),
-    diagnostics: result.diagnostics.into_iter().collect(),
+    diagnostics: result.diagnostics.into_iter().map(Into::into).collect(),
     stat: convert_stats(result.fn_stats, result.mangling_stats),
   }
 }
@@ -202,38 +282,42 @@ pub fn shake_single_module(source_text: String, options: Options) -> SingleModul
 #[napi(object)]
 pub struct MultiModuleResult {
   pub output: HashMap<String, Chunk>,
-  pub diagnostics: Vec<String>,
+  pub diagnostics: Vec<Diagnostic>,
   pub stat: Stat,
 }
 
 #[napi]
 pub fn shake_multi_module(
   sources: HashMap<String, String>,
-  entry: String,
+  entries: Vec<Entry>,
   options: Options,
+  source_maps: Option<HashMap<String, String>>,
 ) -> MultiModuleResult {
-  let result = jsshaker::tree_shake(resolve_options(MultiModuleFs(sources), options), entry);
+  let entries = entries.into_iter().map(Into::into).collect();
+  let vfs = MultiModuleFs::new(sources).with_source_maps(source_maps.unwrap_or_default());
+  let result = jsshaker::tree_shake(resolve_options(vfs, entries, options));
   let mut output = HashMap::default();
   for (entry, codegen_result) in result.codegen_return {
     output.insert(entry, codegen_result.into());
   }
   MultiModuleResult {
     output,
-    diagnostics: result.diagnostics.into_iter().collect(),
+    diagnostics: result.diagnostics.into_iter().map(Into::into).collect(),
     stat: convert_stats(result.fn_stats, result.mangling_stats),
   }
 }
 
 #[napi]
 pub fn shake_fs_module(entry_path: String, options: Options) -> MultiModuleResult {
-  let result = jsshaker::tree_shake(resolve_options(StdFs, options), entry_path.clone());
+  let result =
+    jsshaker::tree_shake(resolve_options(StdFs, vec![JsShakerEntry::new(entry_path)], options));
   let mut output = HashMap::default();
   for (entry, codegen_result) in result.codegen_return {
     output.insert(entry, codegen_result.into());
   }
   MultiModuleResult {
     output,
-    diagnostics: result.diagnostics.into_iter().collect(),
+    diagnostics: result.diagnostics.into_iter().map(Into::into).collect(),
     stat: convert_stats(result.fn_stats, result.mangling_stats),
   }
 }
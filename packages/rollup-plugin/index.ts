@@ -41,6 +41,12 @@ export default function rollupPluginJsShaker(
           preset: pluginOptions.preset,
           advanced: pluginOptions.advanced,
           jsx: "react",
+          sourceKind:
+            outputOptions.format === "es"
+              ? "module"
+              : outputOptions.format === "cjs"
+                ? "auto"
+                : "script",
           sourceMap: !!outputOptions.sourcemap,
           minify:
             "minify" in outputOptions
@@ -49,35 +55,41 @@ export default function rollupPluginJsShaker(
               : !!minify,
         };
 
-        const entrySource = Object.values(bundle)
+        const entries = Object.values(bundle)
           .filter((module) => module.isEntry)
-          .map((b) => b.fileName)
-          .flatMap((name, i) => [
-            `export * as e${i.toString(36)} from "./${name}";`,
-            `export { default as d${i.toString(36)} } from "./${name}";`,
-          ])
-          .join("\n");
+          .map((module) => ({ path: module.fileName }));
 
-        const entryFileName = "___entry___.js";
-        const sources: Record<string, string> = {
-          [entryFileName]: entrySource,
-        };
+        const sources: Record<string, string> = {};
+        const sourceMaps: Record<string, string> = {};
         for (const [fileName, module] of Object.entries(bundle)) {
           sources[fileName] = module.code;
+          if (module.map) {
+            sourceMaps[fileName] = module.map.toString();
+          }
         }
 
         const startTime = Date.now();
         this.info(`Optimizing chunks...`);
-        const shaken = shakeMultiModule(sources, entryFileName, options);
+        const shaken = shakeMultiModule(sources, entries, options, sourceMaps);
         this.info(`Completed in ${Date.now() - startTime} ms`);
 
         if (pluginOptions.showWarnings) {
           for (const diag of shaken.diagnostics) {
-            this.warn(`${diag}`);
+            this.warn({
+              message: `[${diag.code}] ${diag.message}`,
+              id: diag.path ?? undefined,
+              loc:
+                diag.path && diag.startLine && diag.startColumn
+                  ? {
+                      file: diag.path,
+                      line: diag.startLine,
+                      column: diag.startColumn - 1,
+                    }
+                  : undefined,
+            });
           }
         }
 
-        delete shaken.output[entryFileName];
         const maxFileNameLength = Math.max(
           ...Object.keys(shaken.output).map((n) => n.length),
         );
@@ -96,9 +108,9 @@ export default function rollupPluginJsShaker(
             totalOriginalSize += module.code.length;
             totalShakenSize += chunk.code.length;
             module.code = chunk.code;
-            // if (chunk.sourceMapJson) {
-            //   module.map = JSON.parse(chunk.sourceMapJson);
-            // }
+            if (module.map && chunk.sourceMapJson) {
+              Object.assign(module.map, JSON.parse(chunk.sourceMapJson));
+            }
           } else {
             throw new Error(
               `JsShaker Vite plugin expected to find module ${fileName} in the bundle.`,
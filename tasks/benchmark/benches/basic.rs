@@ -1,19 +1,17 @@
 use std::{fs::read_to_string, path::Path};
 
 use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
-use jsshaker::{JsShakerOptions, TreeShakeConfig, tree_shake, vfs::SingleFileFs};
+use jsshaker::{JsShakerEntry, JsShakerOptions, TreeShakeConfig, tree_shake, vfs::SingleFileFs};
 
 fn run_jsshaker(source_text: String) -> String {
-  let result = tree_shake(
-    JsShakerOptions {
-      vfs: SingleFileFs(source_text),
-      config: TreeShakeConfig::recommended(),
-      minify_options: None,
-      codegen_options: Default::default(),
-      source_map: false,
-    },
-    SingleFileFs::ENTRY_PATH.to_string(),
-  );
+  let result = tree_shake(JsShakerOptions {
+    vfs: SingleFileFs(source_text),
+    entries: vec![JsShakerEntry::new(SingleFileFs::ENTRY_PATH)],
+    config: TreeShakeConfig::recommended(),
+    minify_options: None,
+    codegen_options: Default::default(),
+    source_map: false,
+  });
 
   result.codegen_return[SingleFileFs::ENTRY_PATH].code.clone()
 }